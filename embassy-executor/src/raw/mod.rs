@@ -21,6 +21,8 @@ use core::pin::Pin;
 use core::ptr::NonNull;
 use core::task::{Context, Poll};
 
+#[cfg(feature = "task-stats")]
+use atomic_polyfill::AtomicUsize;
 use atomic_polyfill::{AtomicU32, Ordering};
 #[cfg(feature = "integrated-timers")]
 use embassy_time::driver::{self, AlarmHandle};
@@ -161,6 +163,12 @@ impl<F: Future + 'static> TaskStorage<F> {
         match future.poll(&mut cx) {
             Poll::Ready(_) => {
                 this.future.drop_in_place();
+
+                #[cfg(feature = "task-stats")]
+                if let Some(executor) = this.raw.executor.get() {
+                    executor.task_count.fetch_sub(1, Ordering::Relaxed);
+                }
+
                 this.raw.state.fetch_and(!STATE_SPAWNED, Ordering::AcqRel);
 
                 #[cfg(feature = "integrated-timers")]
@@ -322,6 +330,8 @@ impl Pender {
 pub(crate) struct SyncExecutor {
     run_queue: RunQueue,
     pender: Pender,
+    #[cfg(feature = "task-stats")]
+    task_count: AtomicUsize,
 
     #[cfg(feature = "integrated-timers")]
     pub(crate) timer_queue: timer_queue::TimerQueue,
@@ -337,6 +347,8 @@ impl SyncExecutor {
         Self {
             run_queue: RunQueue::new(),
             pender,
+            #[cfg(feature = "task-stats")]
+            task_count: AtomicUsize::new(0),
 
             #[cfg(feature = "integrated-timers")]
             timer_queue: timer_queue::TimerQueue::new(),
@@ -373,6 +385,9 @@ impl SyncExecutor {
         #[cfg(feature = "rtos-trace")]
         trace::task_new(task.as_ptr() as u32);
 
+        #[cfg(feature = "task-stats")]
+        self.task_count.fetch_add(1, Ordering::Relaxed);
+
         self.enqueue(task);
     }
 
@@ -539,6 +554,24 @@ impl Executor {
     pub fn spawner(&'static self) -> super::Spawner {
         super::Spawner::new(self)
     }
+
+    /// Number of tasks spawned in this executor that haven't finished yet.
+    ///
+    /// This is meant for diagnostics only: with tasks being spawned from other threads or
+    /// interrupts, the value can be outdated as soon as it's returned.
+    #[cfg(feature = "task-stats")]
+    pub fn task_count(&self) -> usize {
+        self.inner.task_count.load(Ordering::Relaxed)
+    }
+
+    /// Number of tasks currently waiting in the run queue, i.e. woken and not polled yet.
+    ///
+    /// A task that finished after being woken can still be counted until the next `poll`. Like
+    /// [`task_count`](Self::task_count), this is meant for diagnostics only.
+    #[cfg(feature = "task-stats")]
+    pub fn run_queue_len(&self) -> usize {
+        self.inner.run_queue.len()
+    }
 }
 
 /// Wake a task by `TaskRef`.
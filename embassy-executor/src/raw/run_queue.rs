@@ -1,6 +1,8 @@
 use core::ptr;
 use core::ptr::NonNull;
 
+#[cfg(feature = "task-stats")]
+use atomic_polyfill::AtomicUsize;
 use atomic_polyfill::{AtomicPtr, Ordering};
 
 use super::{TaskHeader, TaskRef};
@@ -31,15 +33,25 @@ impl RunQueueItem {
 /// by waking its own waker) can't prevent other tasks from running.
 pub(crate) struct RunQueue {
     head: AtomicPtr<TaskHeader>,
+    #[cfg(feature = "task-stats")]
+    len: AtomicUsize,
 }
 
 impl RunQueue {
     pub const fn new() -> Self {
         Self {
             head: AtomicPtr::new(ptr::null_mut()),
+            #[cfg(feature = "task-stats")]
+            len: AtomicUsize::new(0),
         }
     }
 
+    /// Number of tasks currently in the queue.
+    #[cfg(feature = "task-stats")]
+    pub(crate) fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
     /// Enqueues an item. Returns true if the queue was empty.
     ///
     /// # Safety
@@ -49,6 +61,11 @@ impl RunQueue {
     pub(crate) unsafe fn enqueue(&self, task: TaskRef) -> bool {
         let mut was_empty = false;
 
+        // Count the task before publishing it: once it's in the queue, a concurrent `dequeue_all`
+        // can already take it out and decrement `len`, which would otherwise underflow.
+        #[cfg(feature = "task-stats")]
+        self.len.fetch_add(1, Ordering::Relaxed);
+
         self.head
             .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |prev| {
                 was_empty = prev.is_null();
@@ -82,6 +99,9 @@ impl RunQueue {
             // safety: there are no concurrent accesses to `next`
             next = unsafe { task.header().run_queue_item.next.get() };
 
+            #[cfg(feature = "task-stats")]
+            self.len.fetch_sub(1, Ordering::Relaxed);
+
             on_task(task);
         }
     }
@@ -134,6 +134,13 @@ impl Spawner {
     pub fn make_send(&self) -> SendSpawner {
         SendSpawner::new(&self.executor.inner)
     }
+
+    /// Get the executor this spawner spawns into, e.g. to query its
+    /// [`task_count`](raw::Executor::task_count).
+    #[cfg(feature = "task-stats")]
+    pub fn executor(&self) -> &'static raw::Executor {
+        self.executor
+    }
 }
 
 /// Handle to spawn tasks into an executor from any thread.
@@ -761,6 +761,11 @@ fn main() {
         (("quadspi", "QUADSPI"), quote!(crate::qspi::QuadDma)),
         (("dac", "CH1"), quote!(crate::dac::DmaCh1)),
         (("dac", "CH2"), quote!(crate::dac::DmaCh2)),
+        (("adc", "ADC"), quote!(crate::adc::RxDma)),
+        (("adc", "ADC1"), quote!(crate::adc::RxDma)),
+        (("adc", "ADC2"), quote!(crate::adc::RxDma)),
+        (("adc", "ADC3"), quote!(crate::adc::RxDma)),
+        (("adc", "ADC4"), quote!(crate::adc::RxDma)),
     ]
     .into();
 
@@ -809,6 +814,41 @@ fn main() {
         }
     }
 
+    // ========
+    // Generate ADC_CHANNEL_COUNT
+
+    // On ADCs selecting channels with a CHSELR bitmask, there's one bit per channel, including
+    // the internal ones.
+    for p in METADATA.peripherals {
+        let Some(regs) = &p.registers else { continue };
+        if regs.kind != "adc" {
+            continue;
+        }
+        let Some(chselr) = regs
+            .ir
+            .fieldsets
+            .iter()
+            .find(|fs| fs.name.eq_ignore_ascii_case("chselr"))
+        else {
+            continue;
+        };
+
+        let channel_count: usize = chselr
+            .fields
+            .iter()
+            .map(|f| match &f.array {
+                Some(stm32_metapac::metadata::ir::Array::Regular(a)) => a.len as usize,
+                Some(stm32_metapac::metadata::ir::Array::Cursed(a)) => a.offsets.len(),
+                None => 1,
+            })
+            .sum();
+
+        g.extend(quote! {
+            pub(crate) const ADC_CHANNEL_COUNT: usize = #channel_count;
+        });
+        break;
+    }
+
     // ========
     // Write foreach_foo! macrotables
 
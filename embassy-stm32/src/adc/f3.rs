@@ -14,14 +14,33 @@ pub const VDDA_CALIB_MV: u32 = 3300;
 pub const ADC_MAX: u32 = (1 << 12) - 1;
 pub const VREF_INT: u32 = 1230;
 
+/// How often the ADC status is polled while waiting for it to be disabled or ready, before
+/// giving up. That's a few ms at the highest core clock, far longer than the ADC needs.
+const SPIN_LIMIT: u32 = 100_000;
+
 /// Interrupt handler.
+///
+/// Some instances share an interrupt vector, like ADC1 and ADC2 on `ADC1_2`. The handler only
+/// reacts to its own instance's flags, so bind the handlers of all instances on the vector in a
+/// single entry, and each `Adc` is woken by its own:
+///
+/// ```ignore
+/// bind_interrupts!(struct Irqs {
+///     ADC1_2 => adc::InterruptHandler<ADC1>, adc::InterruptHandler<ADC2>;
+/// });
+///
+/// let adc1 = Adc::new(p.ADC1, Irqs, &mut Delay);
+/// let adc2 = Adc::new(p.ADC2, Irqs, &mut Delay);
+/// ```
 pub struct InterruptHandler<T: Instance> {
     _phantom: PhantomData<T>,
 }
 
 impl<T: Instance> interrupt::typelevel::Handler<T::Interrupt> for InterruptHandler<T> {
     unsafe fn on_interrupt() {
-        if T::regs().isr().read().eoc() {
+        // The flag can still be set from a finished conversion when the other instance on the
+        // vector fires, so only handle it while this instance is waiting for it.
+        if T::regs().isr().read().eoc() && T::regs().ier().read().eocie() {
             T::regs().ier().modify(|w| w.set_eocie(false));
         } else {
             return;
@@ -31,6 +50,90 @@ impl<T: Instance> interrupt::typelevel::Handler<T::Interrupt> for InterruptHandl
     }
 }
 
+/// Mode of a pair of ADCs sharing a common block, like ADC1 and ADC2, see [`Adc::set_dual_mode`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DualMode {
+    /// Both ADCs convert independently, which is the reset state.
+    Independent,
+    /// The conversions of both ADCs are started together by the master ADC.
+    RegularSimultaneous,
+    /// The slave ADC converts a fixed delay after the master, so the same channel on both is
+    /// sampled at up to twice the rate of a single ADC.
+    Interleaved,
+}
+
+impl DualMode {
+    /// Value of `DUAL` in `CCR`.
+    fn bits(self) -> u8 {
+        match self {
+            DualMode::Independent => 0b00000,
+            DualMode::RegularSimultaneous => 0b00110,
+            DualMode::Interleaved => 0b00111,
+        }
+    }
+}
+
+/// Master ADC of a pair, the only one whose driver can select the [`DualMode`].
+pub trait DualInstance: Instance {}
+
+foreach_peripheral!(
+    (adc, ADC1) => {
+        impl DualInstance for crate::peripherals::ADC1 {}
+    };
+    (adc, ADC3) => {
+        impl DualInstance for crate::peripherals::ADC3 {}
+    };
+);
+
+/// ADC error.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The ADC didn't acknowledge being disabled or enabled.
+    Timeout,
+}
+
+impl<'d, T: DualInstance> Adc<'d, T> {
+    /// Select the mode of the pair of ADCs this one belongs to.
+    ///
+    /// Only the master of the pair, ADC1 or ADC3, has this; start the conversions from it and the
+    /// slave, ADC2 or ADC4, follows. The mode can only be changed while both ADCs are disabled,
+    /// so this ADC is briefly disabled and re-enabled. The other ADC of the pair must not be
+    /// enabled yet, i.e. set the mode before creating its driver.
+    ///
+    /// Fails with [`Error::Timeout`] if the ADC doesn't acknowledge being disabled or enabled
+    /// again. It's left disabled in the first case, with the mode unchanged.
+    pub fn set_dual_mode(&mut self, mode: DualMode) -> Result<(), Error> {
+        use crate::pac::adccommon::vals::Dual;
+
+        assert!(!T::regs().cr().read().adstart(), "ADC is converting");
+
+        T::regs().cr().modify(|w| w.set_addis(true));
+        if !spin_until(|| !T::regs().cr().read().aden()) {
+            return Err(Error::Timeout);
+        }
+
+        T::common_regs()
+            .ccr()
+            .modify(|w| w.set_dual(Dual::from_bits(mode.bits())));
+
+        // ADRDY is still set from when the ADC was enabled first.
+        T::regs().isr().write(|w| w.set_adrdy(true));
+        T::regs().cr().modify(|w| w.set_aden(true));
+        if !spin_until(|| T::regs().isr().read().adrdy()) {
+            return Err(Error::Timeout);
+        }
+
+        Ok(())
+    }
+}
+
+/// Poll `done` up to [`SPIN_LIMIT`] times, returning whether it became true.
+fn spin_until(mut done: impl FnMut() -> bool) -> bool {
+    (0..SPIN_LIMIT).any(|_| done())
+}
+
 pub struct Vref;
 impl<T: Instance> AdcPin<T> for Vref {}
 impl<T: Instance> super::sealed::AdcPin<T> for Vref {
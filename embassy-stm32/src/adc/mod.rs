@@ -28,15 +28,59 @@ pub struct Adc<'d, T: Instance> {
     adc: crate::PeripheralRef<'d, T>,
     #[cfg(not(adc_f3_v2))]
     sample_time: SampleTime,
+    #[cfg(adc_v1)]
+    channel_sample_times: [Option<SampleTime>; _version::CHANNEL_COUNT],
+    #[cfg(adc_v1)]
+    calibration_factor: u8,
+    #[cfg(adc_v1)]
+    pending_channel: Option<u8>,
+    #[cfg(adc_v1)]
+    vdda_calib_mv: u32,
+    /// Offset, gain numerator and gain denominator applied by `read`.
+    #[cfg(adc_v1)]
+    correction: (i16, u16, u16),
 }
 
 pub(crate) mod sealed {
+    #[cfg(adc_v1)]
+    use atomic_polyfill::{AtomicBool, AtomicPtr, AtomicU16, AtomicU8, AtomicUsize};
     #[cfg(any(adc_f3, adc_v1))]
     use embassy_sync::waitqueue::AtomicWaker;
 
     #[cfg(any(adc_f3, adc_v1))]
     pub struct State {
         pub waker: AtomicWaker,
+        /// Set while the ADC is converting continuously. The interrupt handler then keeps `eocie`
+        /// enabled and moves every sample out of `dr` into `sample`.
+        #[cfg(adc_v1)]
+        pub continuous: AtomicBool,
+        #[cfg(adc_v1)]
+        pub sample: AtomicU16,
+        #[cfg(adc_v1)]
+        pub sample_ready: AtomicBool,
+        /// Set by the interrupt handler when a continuous conversion overran, until the stream
+        /// reports it.
+        #[cfg(adc_v1)]
+        pub overrun: AtomicBool,
+        /// Buffer of a burst capture, filled by the interrupt handler up to `burst_len` samples.
+        /// `burst_len` is zero when no capture is running.
+        #[cfg(adc_v1)]
+        pub burst_buf: AtomicPtr<u16>,
+        #[cfg(adc_v1)]
+        pub burst_len: AtomicUsize,
+        #[cfg(adc_v1)]
+        pub burst_pos: AtomicUsize,
+        /// Set while a driver owns the ADC, to catch a second one created through unsafe code.
+        #[cfg(adc_v1)]
+        pub claimed: AtomicBool,
+        /// Number of live `Vref`, `Temperature` and `Vbat` markers. The channel is only turned off
+        /// when the last one is dropped.
+        #[cfg(adc_v1)]
+        pub vref_users: AtomicU8,
+        #[cfg(adc_v1)]
+        pub temperature_users: AtomicU8,
+        #[cfg(adc_v1)]
+        pub vbat_users: AtomicU8,
     }
 
     #[cfg(any(adc_f3, adc_v1))]
@@ -44,6 +88,28 @@ pub(crate) mod sealed {
         pub const fn new() -> Self {
             Self {
                 waker: AtomicWaker::new(),
+                #[cfg(adc_v1)]
+                continuous: AtomicBool::new(false),
+                #[cfg(adc_v1)]
+                sample: AtomicU16::new(0),
+                #[cfg(adc_v1)]
+                sample_ready: AtomicBool::new(false),
+                #[cfg(adc_v1)]
+                overrun: AtomicBool::new(false),
+                #[cfg(adc_v1)]
+                burst_buf: AtomicPtr::new(core::ptr::null_mut()),
+                #[cfg(adc_v1)]
+                burst_len: AtomicUsize::new(0),
+                #[cfg(adc_v1)]
+                burst_pos: AtomicUsize::new(0),
+                #[cfg(adc_v1)]
+                claimed: AtomicBool::new(false),
+                #[cfg(adc_v1)]
+                vref_users: AtomicU8::new(0),
+                #[cfg(adc_v1)]
+                temperature_users: AtomicU8::new(0),
+                #[cfg(adc_v1)]
+                vbat_users: AtomicU8::new(0),
             }
         }
     }
@@ -64,6 +130,9 @@ pub(crate) mod sealed {
 
     pub trait AdcPin<T: Instance> {
         fn channel(&self) -> u8;
+
+        /// Put the pin into analog mode, if it's a GPIO.
+        fn configure_analog(&mut self) {}
     }
 
     pub trait InternalChannel<T> {
@@ -77,7 +146,31 @@ pub trait Instance: sealed::Instance + crate::Peripheral<P = Self> {}
 pub trait Instance: sealed::Instance + crate::Peripheral<P = Self> + crate::rcc::RccPeripheral {}
 
 pub trait AdcPin<T: Instance>: sealed::AdcPin<T> {}
-pub trait InternalChannel<T>: sealed::InternalChannel<T> {}
+
+/// A [`Flex`](crate::gpio::Flex) pin can be used as an ADC input too, for pins that switch between
+/// digital and analog use. Reading it puts it into analog mode, and it stays there until it's
+/// explicitly reconfigured with e.g. [`Flex::set_as_input`](crate::gpio::Flex::set_as_input).
+#[cfg(adc_v1)]
+impl<'d, T: Instance, P: crate::gpio::Pin + AdcPin<T>> AdcPin<T> for crate::gpio::Flex<'d, P> {}
+#[cfg(adc_v1)]
+impl<'d, T: Instance, P: crate::gpio::Pin + AdcPin<T>> sealed::AdcPin<T> for crate::gpio::Flex<'d, P> {
+    fn channel(&self) -> u8 {
+        sealed::AdcPin::<T>::channel(&*self.pin)
+    }
+
+    fn configure_analog(&mut self) {
+        self.set_as_analog();
+    }
+}
+
+pub trait InternalChannel<T>: sealed::InternalChannel<T> {
+    /// Human-readable name of the channel, for logging.
+    fn name(&self) -> &'static str {
+        "internal"
+    }
+}
+
+dma_trait!(RxDma, Instance);
 
 foreach_adc!(
     ($inst:ident, $common_inst:ident, $clock:ident) => {
@@ -123,6 +216,18 @@ macro_rules! impl_adc_pin {
             fn channel(&self) -> u8 {
                 $ch
             }
+
+            fn configure_analog(&mut self) {
+                crate::gpio::sealed::Pin::set_as_analog(self);
+            }
+        }
+
+        impl embedded_hal_02::adc::Channel<peripherals::$inst> for crate::peripherals::$pin {
+            type ID = u8;
+
+            fn channel() -> u8 {
+                $ch
+            }
         }
     };
 }
@@ -1,5 +1,6 @@
 #[cfg(any(adc_v1, adc_v2, adc_v3, adc_g0, adc_f3))]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Resolution {
     TwelveBit,
     TenBit,
@@ -9,6 +10,7 @@ pub enum Resolution {
 
 #[cfg(adc_v4)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Resolution {
     SixteenBit,
     FourteenBit,
@@ -30,6 +32,21 @@ impl Default for Resolution {
     }
 }
 
+/// Resolutions are ordered by their number of bits, so `TwelveBit > EightBit`.
+impl PartialOrd for Resolution {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Resolution {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.bits().cmp(&other.bits())
+    }
+}
+
+/// Every resolution the enum can hold on a family has an encoding in that family's `RES` field, so
+/// the conversion is total: a variant without one doesn't compile in.
 impl From<Resolution> for crate::pac::adc::vals::Res {
     fn from(res: Resolution) -> crate::pac::adc::vals::Res {
         match res {
@@ -46,7 +63,39 @@ impl From<Resolution> for crate::pac::adc::vals::Res {
     }
 }
 
+#[cfg(adc_v1)]
+impl From<crate::pac::adc::vals::Res> for Resolution {
+    fn from(res: crate::pac::adc::vals::Res) -> Resolution {
+        match res {
+            crate::pac::adc::vals::Res::TWELVEBIT => Resolution::TwelveBit,
+            crate::pac::adc::vals::Res::TENBIT => Resolution::TenBit,
+            crate::pac::adc::vals::Res::EIGHTBIT => Resolution::EightBit,
+            crate::pac::adc::vals::Res::SIXBIT => Resolution::SixBit,
+        }
+    }
+}
+
 impl Resolution {
+    /// Number of bits of a sample.
+    pub fn bits(self) -> u8 {
+        match self {
+            #[cfg(adc_v4)]
+            Resolution::SixteenBit => 16,
+            #[cfg(adc_v4)]
+            Resolution::FourteenBit => 14,
+            Resolution::TwelveBit => 12,
+            Resolution::TenBit => 10,
+            Resolution::EightBit => 8,
+            #[cfg(any(adc_v1, adc_v2, adc_v3, adc_g0, adc_f3))]
+            Resolution::SixBit => 6,
+        }
+    }
+
+    /// Largest right-aligned sample value, e.g. 4095 for [`Resolution::TwelveBit`].
+    pub fn max_count(self) -> u16 {
+        ((1u32 << self.bits()) - 1) as u16
+    }
+
     pub fn to_max_count(&self) -> u32 {
         match self {
             #[cfg(adc_v4)]
@@ -61,3 +110,54 @@ impl Resolution {
         }
     }
 }
+
+#[cfg(all(test, adc_v1))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn register_value_round_trips() {
+        for resolution in [
+            Resolution::TwelveBit,
+            Resolution::TenBit,
+            Resolution::EightBit,
+            Resolution::SixBit,
+        ] {
+            let res: crate::pac::adc::vals::Res = resolution.into();
+            assert_eq!(resolution, Resolution::from(res));
+        }
+    }
+
+    #[test]
+    fn register_encoding_fits_res_field() {
+        for (resolution, bits) in [
+            (Resolution::TwelveBit, 0b00),
+            (Resolution::TenBit, 0b01),
+            (Resolution::EightBit, 0b10),
+            (Resolution::SixBit, 0b11),
+        ] {
+            let res: crate::pac::adc::vals::Res = resolution.into();
+            assert_eq!(bits, res.to_bits());
+            assert_eq!(
+                resolution,
+                Resolution::from(crate::pac::adc::vals::Res::from_bits(bits))
+            );
+        }
+    }
+
+    #[test]
+    fn max_count_matches_bits() {
+        assert_eq!(4095, Resolution::TwelveBit.max_count());
+        assert_eq!(1023, Resolution::TenBit.max_count());
+        assert_eq!(255, Resolution::EightBit.max_count());
+        assert_eq!(63, Resolution::SixBit.max_count());
+    }
+
+    #[test]
+    fn ordered_by_bits() {
+        assert!(Resolution::SixBit < Resolution::EightBit);
+        assert!(Resolution::EightBit < Resolution::TenBit);
+        assert!(Resolution::TenBit < Resolution::TwelveBit);
+        assert_eq!(Resolution::TwelveBit, Resolution::TenBit.max(Resolution::TwelveBit));
+    }
+}
@@ -1,8 +1,9 @@
 #[cfg(not(adc_f3_v2))]
 macro_rules! impl_sample_time {
     ($default_doc:expr, $default:ident, ($(($doc:expr, $variant:ident, $pac_variant:ident)),*)) => {
-        #[doc = concat!("ADC sample time\n\nThe default setting is ", $default_doc, " ADC clock cycles.")]
+        #[doc = concat!("ADC sample time\n\nThe default setting is ", $default_doc, " ADC clock cycles. Sample times are ordered by their length.")]
         #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+        #[cfg_attr(feature = "defmt", derive(defmt::Format))]
         pub enum SampleTime {
             $(
                 #[doc = concat!($doc, " ADC clock cycles.")]
@@ -42,6 +43,69 @@ impl_sample_time!(
     )
 );
 
+#[cfg(any(adc_f1, adc_v1))]
+impl SampleTime {
+    const ALL: [SampleTime; 8] = [
+        SampleTime::Cycles1_5,
+        SampleTime::Cycles7_5,
+        SampleTime::Cycles13_5,
+        SampleTime::Cycles28_5,
+        SampleTime::Cycles41_5,
+        SampleTime::Cycles55_5,
+        SampleTime::Cycles71_5,
+        SampleTime::Cycles239_5,
+    ];
+
+    /// The shortest sample time of at least `cycles` ADC clock cycles.
+    ///
+    /// Saturates at the longest sample time.
+    pub fn from_min_cycles(cycles: u16) -> SampleTime {
+        let half_cycles = 2 * cycles as u32;
+
+        Self::ALL
+            .into_iter()
+            .find(|sample_time| sample_time.half_cycles() >= half_cycles)
+            .unwrap_or(SampleTime::Cycles239_5)
+    }
+
+    /// Length of the sample time in ADC clock cycles, e.g. 1.5 for [`SampleTime::Cycles1_5`].
+    pub fn cycles(self) -> f32 {
+        self.half_cycles() as f32 / 2.0
+    }
+
+    /// Length of the sample time in half ADC clock cycles, which is always a whole number.
+    pub(crate) fn half_cycles(self) -> u32 {
+        match self {
+            SampleTime::Cycles1_5 => 3,
+            SampleTime::Cycles7_5 => 15,
+            SampleTime::Cycles13_5 => 27,
+            SampleTime::Cycles28_5 => 57,
+            SampleTime::Cycles41_5 => 83,
+            SampleTime::Cycles55_5 => 111,
+            SampleTime::Cycles71_5 => 143,
+            SampleTime::Cycles239_5 => 479,
+        }
+    }
+}
+
+#[cfg(adc_v1)]
+impl From<crate::pac::adc::vals::SampleTime> for SampleTime {
+    fn from(sample_time: crate::pac::adc::vals::SampleTime) -> SampleTime {
+        use crate::pac::adc::vals::SampleTime as Smp;
+
+        match sample_time {
+            Smp::CYCLES1_5 => SampleTime::Cycles1_5,
+            Smp::CYCLES7_5 => SampleTime::Cycles7_5,
+            Smp::CYCLES13_5 => SampleTime::Cycles13_5,
+            Smp::CYCLES28_5 => SampleTime::Cycles28_5,
+            Smp::CYCLES41_5 => SampleTime::Cycles41_5,
+            Smp::CYCLES55_5 => SampleTime::Cycles55_5,
+            Smp::CYCLES71_5 => SampleTime::Cycles71_5,
+            Smp::CYCLES239_5 => SampleTime::Cycles239_5,
+        }
+    }
+}
+
 #[cfg(adc_v2)]
 impl_sample_time!(
     "3",
@@ -121,3 +185,42 @@ impl_sample_time!(
         ("601.5", Cycles601_5, CYCLES601_5)
     )
 );
+
+#[cfg(all(test, adc_v1))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_min_cycles_picks_shortest_sufficient() {
+        assert_eq!(SampleTime::Cycles1_5, SampleTime::from_min_cycles(0));
+        assert_eq!(SampleTime::Cycles1_5, SampleTime::from_min_cycles(1));
+        assert_eq!(SampleTime::Cycles7_5, SampleTime::from_min_cycles(2));
+        assert_eq!(SampleTime::Cycles71_5, SampleTime::from_min_cycles(56));
+        assert_eq!(SampleTime::Cycles239_5, SampleTime::from_min_cycles(72));
+        assert_eq!(SampleTime::Cycles239_5, SampleTime::from_min_cycles(u16::MAX));
+    }
+
+    #[test]
+    fn cycles_round_trip() {
+        for sample_time in SampleTime::ALL {
+            assert_eq!(sample_time, SampleTime::from_min_cycles(sample_time.cycles() as u16));
+        }
+    }
+
+    #[test]
+    fn register_value_round_trips() {
+        for sample_time in SampleTime::ALL {
+            let smp: crate::pac::adc::vals::SampleTime = sample_time.into();
+            assert_eq!(sample_time, SampleTime::from(smp));
+        }
+    }
+
+    #[test]
+    fn ordered_by_cycles() {
+        for pair in SampleTime::ALL.windows(2) {
+            assert!(pair[0] < pair[1]);
+            assert!(pair[0].cycles() < pair[1].cycles());
+        }
+        assert!(SampleTime::Cycles28_5 < SampleTime::Cycles55_5);
+    }
+}
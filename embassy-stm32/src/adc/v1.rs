@@ -1,194 +1,3719 @@
-use core::future::poll_fn;
+use core::future::{poll_fn, Future};
 use core::marker::PhantomData;
-use core::task::Poll;
+use core::pin::{pin, Pin};
+use core::sync::atomic::Ordering;
+use core::task::{Context, Poll};
 
+use atomic_polyfill::AtomicU8;
+use embassy_futures::select::{select, Either};
+use embassy_hal_internal::drop::OnDrop;
 use embassy_hal_internal::into_ref;
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::mutex::{Mutex, MutexGuard};
+use embassy_sync::signal::Signal;
+#[cfg(feature = "time")]
+use embassy_time::{Duration, Instant, Ticker, Timer};
 use embedded_hal_02::blocking::delay::DelayUs;
+use futures::Stream;
 
-use crate::adc::{Adc, AdcPin, Instance, InternalChannel, Resolution, SampleTime};
+use crate::adc::{Adc, AdcPin, Instance, InternalChannel, Resolution, RxDma, SampleTime};
+use crate::dma::{ReadableRingBuffer, Transfer, TransferOptions};
 use crate::interrupt::typelevel::Interrupt;
-use crate::peripherals::ADC;
-use crate::{interrupt, Peripheral};
+use crate::pac::adc::vals::{Align, Ckmode, Dmacfg, Exten};
+use crate::time::Hertz;
+use crate::{interrupt, Peripheral, PeripheralRef};
 
+/// VDDA at which the factory calibration values were measured.
+#[cfg(not(stm32l0))]
 pub const VDDA_CALIB_MV: u32 = 3300;
-pub const VREF_INT: u32 = 1230;
+/// VDDA at which the factory calibration values were measured.
+#[cfg(stm32l0)]
+pub const VDDA_CALIB_MV: u32 = 3000;
+
+/// Typical internal reference voltage in millivolts, as given in the family datasheets.
+#[cfg(not(stm32l0))]
+const VREF_INT_TYPICAL_MV: u32 = 1230;
+/// Typical internal reference voltage in millivolts, as given in the family datasheets.
+#[cfg(stm32l0)]
+const VREF_INT_TYPICAL_MV: u32 = 1224;
+
+#[deprecated(note = "the typical value depends on the family, use `Adc::vref_int_typical` instead")]
+pub const VREF_INT: u32 = VREF_INT_TYPICAL_MV;
+
+/// Largest number of channels converted per trigger in discontinuous mode.
+const MAX_DISCONTINUOUS_COUNT: u8 = 1;
+
+/// Temperatures outside of this range fail [`Adc::temperature_sanity_check`]. It's the widest
+/// operating range of the supported parts, -40 to 125 °C, with some margin for the sensor's
+/// inaccuracy.
+#[cfg(any(all(stm32f0, not(stm32f0x0)), stm32l0))]
+const PLAUSIBLE_TEMPERATURE: core::ops::RangeInclusive<f32> = -55.0..=140.0;
+
+/// Correction that leaves samples as they are, see [`Adc::set_correction`].
+const NO_CORRECTION: (i16, u16, u16) = (0, 1, 1);
+
+/// Number of channels, including the internal ones, as the number of CHSELR bits.
+pub(crate) const CHANNEL_COUNT: usize = crate::_generated::ADC_CHANNEL_COUNT;
+
+/// Factory calibration values, programmed into system memory during production.
+///
+/// All of them are 12-bit right-aligned readings, taken with VDDA at the calibration voltage.
+#[allow(unused)]
+mod calibration {
+    #[cfg(stm32f0)]
+    pub const VREFINT_CAL: *const u16 = 0x1FFF_F7BA as _;
+    #[cfg(stm32f0)]
+    pub const TS_CAL1: *const u16 = 0x1FFF_F7B8 as _;
+    #[cfg(stm32f0)]
+    pub const TS_CAL1_TEMP: f32 = 30.0;
+    // The STM32F030/F070 only have the 30 °C point.
+    #[cfg(all(stm32f0, not(stm32f0x0)))]
+    pub const TS_CAL2: *const u16 = 0x1FFF_F7C2 as _;
+    #[cfg(all(stm32f0, not(stm32f0x0)))]
+    pub const TS_CAL2_TEMP: f32 = 110.0;
+
+    #[cfg(stm32l0)]
+    pub const VREFINT_CAL: *const u16 = 0x1FF8_0078 as _;
+    #[cfg(stm32l0)]
+    pub const TS_CAL1: *const u16 = 0x1FF8_007A as _;
+    #[cfg(stm32l0)]
+    pub const TS_CAL1_TEMP: f32 = 30.0;
+    #[cfg(stm32l0)]
+    pub const TS_CAL2: *const u16 = 0x1FF8_007E as _;
+    #[cfg(stm32l0)]
+    pub const TS_CAL2_TEMP: f32 = 130.0;
+
+    pub fn read(addr: *const u16) -> u16 {
+        // Safety: the addresses above are always-readable, read-only system memory.
+        unsafe { core::ptr::read_volatile(addr) }
+    }
+}
+
+/// Hardware oversampling ratio.
+#[cfg(stm32l0)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OversamplingRatio {
+    X2,
+    X4,
+    X8,
+    X16,
+    X32,
+    X64,
+    X128,
+    X256,
+}
+
+/// Right shift applied to the accumulated oversampling result.
+#[cfg(stm32l0)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OversamplingShift {
+    NoShift,
+    Shift1,
+    Shift2,
+    Shift3,
+    Shift4,
+    Shift5,
+    Shift6,
+    Shift7,
+    Shift8,
+}
+
+/// Frequency of the dedicated asynchronous ADC clock.
+#[cfg(not(stm32l0))]
+const ASYNC_CLOCK_FREQ: Hertz = Hertz::mhz(14);
+/// Frequency of the dedicated asynchronous ADC clock.
+#[cfg(stm32l0)]
+const ASYNC_CLOCK_FREQ: Hertz = Hertz::mhz(16);
+
+/// ADC clock source, selected with `CKMODE`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AdcClockSource {
+    /// The dedicated asynchronous ADC clock: HSI14 on the STM32F0, HSI16 on the STM32L0.
+    Async,
+    /// PCLK divided by 2. Being synchronous to the bus, this gives deterministic trigger latency.
+    PclkDiv2,
+    /// PCLK divided by 4.
+    PclkDiv4,
+}
+
+impl Default for AdcClockSource {
+    fn default() -> Self {
+        Self::Async
+    }
+}
+
+impl From<AdcClockSource> for Ckmode {
+    fn from(source: AdcClockSource) -> Ckmode {
+        match source {
+            AdcClockSource::Async => Ckmode::ADCCLK,
+            AdcClockSource::PclkDiv2 => Ckmode::PCLK_DIV2,
+            AdcClockSource::PclkDiv4 => Ckmode::PCLK_DIV4,
+        }
+    }
+}
+
+/// Alignment of the samples in the data register, selected with `ALIGN`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DataAlign {
+    Right,
+    /// Left aligned to bit 15, except for 6-bit samples, which are left aligned to bit 7.
+    Left,
+}
+
+impl Default for DataAlign {
+    fn default() -> Self {
+        Self::Right
+    }
+}
+
+impl From<DataAlign> for Align {
+    fn from(align: DataAlign) -> Align {
+        match align {
+            DataAlign::Right => Align::RIGHT,
+            DataAlign::Left => Align::LEFT,
+        }
+    }
+}
+
+/// Largest value the data register holds for a full-scale input.
+fn full_scale_count(resolution: Resolution, align: DataAlign) -> u32 {
+    let max_count = resolution.max_count() as u32;
+
+    // Left-aligned samples fill a half-word, except for 6-bit ones which only fill a byte.
+    match (align, resolution) {
+        (DataAlign::Right, _) => max_count,
+        (DataAlign::Left, Resolution::SixBit) => max_count << (8 - resolution.bits()),
+        (DataAlign::Left, _) => max_count << (16 - resolution.bits()),
+    }
+}
+
+/// Convert a sample to millivolts, using a [`Vref`] sample taken at the same `resolution`.
+///
+/// This uses the typical internal reference voltage rather than the factory calibration, so it
+/// doesn't need the MCU and can be used e.g. for post-processing logged samples. Both samples must
+/// be right-aligned. The result doesn't depend on `resolution` as long as both samples share it,
+/// it's only used to check that they're in range.
+pub fn sample_to_mv(sample: u16, vref_int_sample: u16, resolution: Resolution) -> u16 {
+    debug_assert!(sample <= resolution.max_count() && vref_int_sample <= resolution.max_count());
+
+    (sample as u32 * VREF_INT_TYPICAL_MV / vref_int_sample as u32) as u16
+}
+
+/// Scale a threshold at `resolution` to the 12-bit value the analog watchdog compares against.
+///
+/// Below 12 bits, the reference manual requires the LSBs the resolution doesn't have to be
+/// cleared, which the shift does.
+fn watchdog_threshold(threshold: u16, resolution: Resolution) -> u16 {
+    threshold.min(resolution.max_count()) << (12 - resolution.bits())
+}
+
+/// The mean of `n` samples adding up to `sum`, rounded to the nearest count.
+///
+/// `sum` is 64-bit so that even `u32::MAX` full scale left-aligned samples don't overflow it.
+fn rounded_mean(sum: u64, n: u32) -> u16 {
+    ((sum + n as u64 / 2) / n as u64) as u16
+}
+
+/// `sample * vdda_calib_mv * vrefint_cal / (vrefint_sample * 4095)`, rounded to the nearest
+/// millivolt, without floats or overflow.
+fn calibrated_mv(sample: u16, vrefint_sample: u16, vrefint_cal: u16, vdda_calib_mv: u32) -> u32 {
+    // The numerator can exceed `u32` already for 12-bit samples, e.g. 4095 * 4095 * 3300.
+    let num = sample as u64 * vrefint_cal as u64 * vdda_calib_mv as u64;
+    let den = vrefint_sample as u64 * Resolution::TwelveBit.to_max_count() as u64;
+    ((num + den / 2) / den) as u32
+}
+
+/// Readings of the internal channels taken together by [`Adc::read_internal_snapshot`].
+#[cfg(any(all(stm32f0, not(stm32f0x0)), stm32l0))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct InternalSnapshot {
+    pub vdda_mv: u32,
+    pub temperature_c: f32,
+    /// The STM32L0 has no VBAT channel.
+    #[cfg(stm32f0)]
+    pub vbat_mv: u32,
+}
+
+/// A sample together with the resolution it was taken at, returned by [`Adc::read_sample`].
+///
+/// Unlike a bare `u16`, this can't be scaled with the full-scale value of another resolution.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Sample {
+    value: u16,
+    resolution: Resolution,
+    /// Value of a full-scale sample, which also depends on the alignment and oversampling.
+    full_scale: u32,
+}
+
+impl Sample {
+    /// The raw sample.
+    pub fn value(&self) -> u16 {
+        self.value
+    }
+
+    /// The resolution the sample was taken at.
+    pub fn resolution(&self) -> Resolution {
+        self.resolution
+    }
+
+    /// Convert the sample to millivolts, given the VDDA it was measured against.
+    pub fn to_mv(&self, vdda_mv: u32) -> u32 {
+        self.value as u32 * vdda_mv / self.full_scale
+    }
+}
+
+impl From<Sample> for u16 {
+    fn from(sample: Sample) -> u16 {
+        sample.value
+    }
+}
+
+/// Hardware trigger source, selected with `EXTSEL`.
+///
+/// The variants are in `EXTSEL` order, TRG0 to TRG4; TRG5 to TRG7 are reserved. Unlike on the
+/// STM32L0, none of them is a GPIO: to start a conversion on an external signal, feed it to a timer
+/// input and have the timer forward it as its `TRGO`, e.g. TIM3 in trigger or reset slave mode on
+/// TI1 or ETR with the master mode set to reset or enable.
+#[cfg(stm32f0)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExternalTrigger {
+    Tim1Trgo,
+    Tim1Cc4,
+    Tim2Trgo,
+    Tim3Trgo,
+    Tim15Trgo,
+}
+
+/// Hardware trigger source, selected with `EXTSEL`.
+///
+/// The variants are in `EXTSEL` order, TRG0 to TRG7. [`Exti11`](Self::Exti11) is EXTI line 11,
+/// so pin 11 of the port selected for it in `SYSCFG_EXTICR3`.
+#[cfg(stm32l0)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExternalTrigger {
+    Tim6Trgo,
+    Tim21Ch2,
+    Tim2Trgo,
+    Tim2Ch4,
+    Tim22Trgo,
+    Tim2Ch3,
+    Tim3Trgo,
+    Exti11,
+}
+
+/// Trigger edge, selected with `EXTEN`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TriggerEdge {
+    /// Hardware trigger disabled, conversions are started by software.
+    None,
+    Rising,
+    Falling,
+    Both,
+}
+
+impl From<TriggerEdge> for Exten {
+    fn from(edge: TriggerEdge) -> Exten {
+        match edge {
+            TriggerEdge::None => Exten::DISABLED,
+            TriggerEdge::Rising => Exten::RISINGEDGE,
+            TriggerEdge::Falling => Exten::FALLINGEDGE,
+            TriggerEdge::Both => Exten::BOTHEDGES,
+        }
+    }
+}
+
+/// ADC error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AdcError {
+    /// The number of channels doesn't match the length of the output buffer.
+    LengthMismatch,
+    /// A new conversion completed before the previous result was read, so `dr` no longer holds
+    /// the requested sample.
+    Overrun,
+    /// The conversion, or the calibration, didn't complete in time. This usually means the ADC
+    /// clock isn't running.
+    Timeout,
+    /// The ADC has been disabled with [`Adc::disable`].
+    Disabled,
+    /// The channels can't be converted together, like VBAT and the temperature sensor, which share
+    /// the same input path on some parts.
+    ConflictingChannels,
+    /// The DMA transfer stopped before all samples were moved.
+    Dma,
+    /// A conversion didn't complete before the next one was due.
+    SampleRateTooHigh,
+    /// A reading is outside of what the hardware can physically produce, see
+    /// [`Adc::temperature_sanity_check`].
+    Implausible,
+    /// The ADC has no channel with this number.
+    InvalidChannel,
+    /// A channel appears twice in a sequence, which the ADC can't convert.
+    DuplicateChannel,
+    /// The asynchronous ADC clock isn't running, so the ADC would never complete its calibration
+    /// or become ready. On the STM32L0, enable HSI16 first. The STM32F0 driver starts HSI14
+    /// itself, so there this means HSI14 didn't become ready.
+    ClockNotReady,
+    /// The read was cancelled through the signal passed to [`Adc::read_cancellable`].
+    Cancelled,
+    /// The ADC didn't become ready after being enabled, even after retrying many times.
+    EnableFailed,
+}
+
+/// ADC configuration, see [`Adc::new_with`].
+#[non_exhaustive]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AdcConfig {
+    /// Calibrate the ADC before enabling it.
+    ///
+    /// Skipping the calibration saves a few tens of microseconds, but leaves the ADC with the
+    /// calibration factor it has after reset: zero on the STM32F0, while the STM32L0 keeps CALFACT
+    /// in some low-power modes. [`Adc::calibration_factor`] then returns zero on the STM32F0.
+    pub calibrate: bool,
+    /// The sample time of all channels, see [`Adc::set_sample_time`].
+    pub sample_time: SampleTime,
+    pub resolution: Resolution,
+    /// The clock the ADC runs from, also for the calibration.
+    pub clock_source: AdcClockSource,
+}
+
+impl Default for AdcConfig {
+    fn default() -> Self {
+        Self {
+            calibrate: true,
+            sample_time: Default::default(),
+            resolution: Default::default(),
+            clock_source: Default::default(),
+        }
+    }
+}
+
+/// Builder for an [`Adc`] with a custom configuration, see [`Adc::builder`].
+///
+/// ```ignore
+/// let mut adc = Adc::builder(p.ADC, Irqs)
+///     .resolution(Resolution::TenBit)
+///     .sample_time(SampleTime::Cycles71_5)
+///     .clock_source(AdcClockSource::PclkDiv4)
+///     .build(&mut Delay);
+/// ```
+pub struct AdcBuilder<'d, T: Instance> {
+    adc: PeripheralRef<'d, T>,
+    config: AdcConfig,
+}
+
+impl<'d, T: Instance> AdcBuilder<'d, T> {
+    /// See [`AdcConfig::sample_time`].
+    pub fn sample_time(mut self, sample_time: SampleTime) -> Self {
+        self.config.sample_time = sample_time;
+        self
+    }
+
+    /// See [`AdcConfig::resolution`].
+    pub fn resolution(mut self, resolution: Resolution) -> Self {
+        self.config.resolution = resolution;
+        self
+    }
+
+    /// See [`AdcConfig::calibrate`].
+    pub fn calibrate(mut self, calibrate: bool) -> Self {
+        self.config.calibrate = calibrate;
+        self
+    }
+
+    /// See [`AdcConfig::clock_source`].
+    pub fn clock_source(mut self, clock_source: AdcClockSource) -> Self {
+        self.config.clock_source = clock_source;
+        self
+    }
+
+    /// Create the driver, like [`Adc::new_with`] with the configuration built so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ADC clock isn't running or the ADC doesn't become ready.
+    pub fn build(self, delay: &mut impl DelayUs<u32>) -> Adc<'d, T> {
+        let adc = Adc::new_inner(self.adc, delay, self.config).expect("ADC failed to start");
+        // `Adc::builder` took the interrupt binding.
+        enable_interrupt::<T>();
+        adc
+    }
+}
+
+/// Raw values of all ADC registers, see [`Adc::dump_registers`].
+///
+/// With `defmt`, the registers are formatted in hex, ready to be pasted into a bug report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdcRegDump {
+    pub isr: u32,
+    pub ier: u32,
+    pub cr: u32,
+    pub cfgr1: u32,
+    pub cfgr2: u32,
+    pub smpr: u32,
+    pub chselr: u32,
+    pub ccr: u32,
+    #[cfg(stm32l0)]
+    pub calfact: u32,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for AdcRegDump {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "isr={=u32:#010x} ier={=u32:#010x} cr={=u32:#010x} cfgr1={=u32:#010x} cfgr2={=u32:#010x} smpr={=u32:#010x} chselr={=u32:#010x} ccr={=u32:#010x}",
+            self.isr,
+            self.ier,
+            self.cr,
+            self.cfgr1,
+            self.cfgr2,
+            self.smpr,
+            self.chselr,
+            self.ccr,
+        );
+        #[cfg(stm32l0)]
+        defmt::write!(fmt, " calfact={=u32:#010x}", self.calfact);
+    }
+}
+
+/// Snapshot of the ADC state, see [`Adc::status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AdcStatus {
+    /// The ADC is enabled and ready to convert (`ADRDY`).
+    pub ready: bool,
+    /// A sample was overwritten before it was read (`OVR`).
+    pub overrun: bool,
+    /// A calibration is running (`ADCAL`).
+    pub calibrating: bool,
+    /// A conversion has been started and not stopped yet (`ADSTART`).
+    pub converting: bool,
+}
+
+/// Calibration factor saved by [`Adc::suspend`], to restore it with [`Adc::resume`].
+#[must_use = "the token is needed to resume the ADC without recalibrating"]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CalibrationToken {
+    factor: u8,
+}
+
+impl CalibrationToken {
+    /// The saved calibration factor.
+    pub fn factor(&self) -> u8 {
+        self.factor
+    }
+}
 
 /// Interrupt handler.
+///
+/// The handler only looks at, and only clears, the ADC's own flags, so it can share its vector with
+/// other handlers. On most STM32F0 parts the vector is `ADC1_COMP`, which also signals the
+/// comparators through their EXTI lines. Bind the handlers for both to the same interrupt, and
+/// they're all called in turn:
+///
+/// ```ignore
+/// struct CompHandler;
+///
+/// impl interrupt::typelevel::Handler<interrupt::typelevel::ADC1_COMP> for CompHandler {
+///     unsafe fn on_interrupt() {
+///         // Check and clear EXTI lines 21 and 22 here.
+///     }
+/// }
+///
+/// bind_interrupts!(struct Irqs {
+///     ADC1_COMP => adc::InterruptHandler<ADC>, CompHandler;
+/// });
+/// ```
+///
+/// Parts with several ADC instances on one vector are bound the same way, with one
+/// `InterruptHandler` per instance in the same entry.
+///
+/// The same mechanism hooks your own code into the ADC interrupt without replacing the driver's
+/// handler, e.g. to toggle a debug pin for measuring the interrupt timing on a scope. The handlers
+/// run in the order they're listed, so one listed after `InterruptHandler` runs right after the
+/// task has been woken, while `EOC` is still set. Nothing is added to the driver's handler for
+/// this, so it costs nothing when unused:
+///
+/// ```ignore
+/// struct TraceHandler;
+///
+/// impl interrupt::typelevel::Handler<interrupt::typelevel::ADC1_COMP> for TraceHandler {
+///     unsafe fn on_interrupt() {
+///         pac::GPIOA.bsrr().write(|w| w.set_bs(5, true));
+///     }
+/// }
+///
+/// bind_interrupts!(struct Irqs {
+///     ADC1_COMP => adc::InterruptHandler<ADC>, TraceHandler;
+/// });
+/// ```
 pub struct InterruptHandler<T: Instance> {
     _phantom: PhantomData<T>,
 }
 
 impl<T: Instance> interrupt::typelevel::Handler<T::Interrupt> for InterruptHandler<T> {
     unsafe fn on_interrupt() {
-        if T::regs().isr().read().eoc() {
+        let state = T::state();
+
+        // Which flag a conversion waits for is selected by the interrupts it enables: EOCIE for
+        // single conversions, EOSIE for sequences. The flags themselves are cleared by the waiting
+        // task, which polls them, or by its drop guard if it's cancelled. Disabling the interrupt
+        // is what prevents it from firing again in the meantime.
+        let isr = T::regs().isr().read();
+        let ier = T::regs().ier().read();
+        if isr.awd() && ier.awdie() {
+            // `watch` sees the disabled interrupt as the watchdog having fired.
+            T::regs().ier().modify(|w| w.set_awdie(false));
+            T::regs().isr().write(|w| w.set_awd(true));
+        } else if isr.eos() && ier.eosie() {
+            T::regs().ier().modify(|w| w.set_eosie(false));
+        } else if isr.eoc() && !isr.ovr() && ier.eocie() && state.burst_len.load(Ordering::Relaxed) != 0 {
+            // Burst capture: store the sample and only wake the task once the buffer is full. An
+            // overrun is handled below, like for single conversions.
+            let pos = state.burst_pos.load(Ordering::Relaxed);
+            let len = state.burst_len.load(Ordering::Relaxed);
+            state
+                .burst_buf
+                .load(Ordering::Relaxed)
+                .add(pos)
+                .write_volatile(T::regs().dr().read().data());
+            state.burst_pos.store(pos + 1, Ordering::Release);
+
+            if pos + 1 < len {
+                return;
+            }
+
             T::regs().ier().modify(|w| w.set_eocie(false));
+            T::regs().cr().modify(|reg| reg.set_adstp(true));
+        } else if (isr.eoc() || isr.ovr()) && state.continuous.load(Ordering::Relaxed) {
+            // Reading DR clears EOC, so the interrupt can stay enabled for the next sample. With
+            // OVRMOD=0 an overrun leaves the older sample in DR, which is dropped along with the
+            // lost one so the stream reports the gap.
+            if isr.ovr() {
+                T::regs().isr().write(|reg| reg.set_ovr(true));
+                let _ = T::regs().dr().read();
+                state.overrun.store(true, Ordering::Release);
+            } else {
+                state.sample.store(T::regs().dr().read().data(), Ordering::Relaxed);
+                state.sample_ready.store(true, Ordering::Release);
+            }
+        } else if (isr.eoc() && ier.eocie()) || (isr.ovr() && ier.ovrie()) {
+            T::regs().ier().modify(|w| {
+                w.set_eocie(false);
+                w.set_ovrie(false);
+            });
         } else {
+            // Not ours, another handler bound to the same vector takes care of it.
             return;
         }
 
-        T::state().waker.wake();
+        state.waker.wake();
+    }
+}
+
+/// The internal channels come after the 16 external ones.
+const FIRST_INTERNAL_CHANNEL: u8 = 16;
+/// The STM32L0 has no VBAT input, channel 18 is its temperature sensor.
+#[cfg(not(stm32l0))]
+const VBAT_CHANNEL: u8 = 18;
+const VREF_CHANNEL: u8 = 17;
+#[cfg(not(stm32l0))]
+const TEMPERATURE_CHANNEL: u8 = 16;
+#[cfg(stm32l0)]
+const TEMPERATURE_CHANNEL: u8 = 18;
+#[cfg(stm32l0)]
+const VLCD_CHANNEL: u8 = 16;
+
+/// Minimum sample times of the internal channels in microseconds, from the datasheets:
+/// ts_vbat, ts_temp and ts_vrefint.
+#[cfg(not(stm32l0))]
+const INTERNAL_MIN_SAMPLE_TIMES_US: [(u8, u32); 3] = [(VBAT_CHANNEL, 4), (TEMPERATURE_CHANNEL, 4), (VREF_CHANNEL, 4)];
+/// Minimum sample times of the internal channels in microseconds, from the datasheets:
+/// ts_temp and ts_vrefint.
+#[cfg(stm32l0)]
+const INTERNAL_MIN_SAMPLE_TIMES_US: [(u8, u32); 2] = [(TEMPERATURE_CHANNEL, 10), (VREF_CHANNEL, 10)];
+
+/// The VBAT channel measures the battery voltage through an internal divider, which divides it by 2
+/// on the STM32F0, to keep it below VDDA.
+#[cfg(all(stm32f0, not(stm32f0x0)))]
+const VBAT_DIVIDER: u32 = 2;
+
+/// Count a new marker of an internal channel in `users`, turning the channel on with `switch_on`.
+///
+/// `switch_on` returns whether the channel was off, which is passed on: it then still needs its
+/// startup time. The critical section keeps a concurrent [`release_internal`] from turning the
+/// channel off in between.
+fn acquire_internal(users: &AtomicU8, switch_on: impl FnOnce() -> bool) -> bool {
+    critical_section::with(|_| {
+        users.fetch_add(1, Ordering::Relaxed);
+        switch_on()
+    })
+}
+
+/// Uncount a marker of an internal channel, turning the channel off with `switch_off` if it was
+/// the last one.
+fn release_internal(users: &AtomicU8, switch_off: impl FnOnce()) {
+    critical_section::with(|_| {
+        if users.fetch_sub(1, Ordering::Relaxed) == 1 {
+            switch_off();
+        }
+    })
+}
+
+/// The VBAT channel. Its divider stays connected until the last `Vbat` is dropped.
+#[cfg(not(stm32l0))]
+pub struct Vbat<T: Instance>(PhantomData<T>);
+#[cfg(not(stm32l0))]
+impl<T: Instance> Vbat<T> {
+    /// Connect the divider if needed. It has no startup time.
+    fn acquire() -> Self {
+        acquire_internal(&T::state().vbat_users, || {
+            T::regs().ccr().modify(|reg| reg.set_vbaten(true));
+            false
+        });
+        Self(PhantomData)
+    }
+}
+#[cfg(not(stm32l0))]
+impl<T: Instance> InternalChannel<T> for Vbat<T> {
+    fn name(&self) -> &'static str {
+        "vbat"
+    }
+}
+#[cfg(not(stm32l0))]
+impl<T: Instance> super::sealed::InternalChannel<T> for Vbat<T> {
+    fn channel(&self) -> u8 {
+        VBAT_CHANNEL
+    }
+}
+#[cfg(not(stm32l0))]
+impl<T: Instance> Drop for Vbat<T> {
+    fn drop(&mut self) {
+        release_internal(&T::state().vbat_users, || {
+            T::regs().ccr().modify(|reg| reg.set_vbaten(false))
+        });
     }
 }
 
-pub struct Vbat;
-impl InternalChannel<ADC> for Vbat {}
-impl super::sealed::InternalChannel<ADC> for Vbat {
+/// The internal reference channel. The reference keeps running until the last `Vref` is dropped.
+pub struct Vref<T: Instance>(PhantomData<T>);
+impl<T: Instance> Vref<T> {
+    /// Turn the reference on if needed, returning whether it was off and so still has to start up.
+    fn acquire() -> (Self, bool) {
+        let was_off = acquire_internal(&T::state().vref_users, || {
+            let was_off = !T::regs().ccr().read().vrefen();
+            T::regs().ccr().modify(|reg| reg.set_vrefen(true));
+            was_off
+        });
+        (Self(PhantomData), was_off)
+    }
+}
+impl<T: Instance> InternalChannel<T> for Vref<T> {
+    fn name(&self) -> &'static str {
+        "vref"
+    }
+}
+impl<T: Instance> super::sealed::InternalChannel<T> for Vref<T> {
     fn channel(&self) -> u8 {
-        18
+        VREF_CHANNEL
+    }
+}
+impl<T: Instance> Drop for Vref<T> {
+    fn drop(&mut self) {
+        release_internal(&T::state().vref_users, || {
+            T::regs().ccr().modify(|reg| reg.set_vrefen(false))
+        });
     }
 }
 
-pub struct Vref;
-impl InternalChannel<ADC> for Vref {}
-impl super::sealed::InternalChannel<ADC> for Vref {
+/// The temperature sensor channel. The sensor keeps running until the last `Temperature` is
+/// dropped.
+pub struct Temperature<T: Instance>(PhantomData<T>);
+impl<T: Instance> Temperature<T> {
+    /// Turn the sensor on if needed, returning whether it was off and so still has to start up.
+    fn acquire() -> (Self, bool) {
+        let was_off = acquire_internal(&T::state().temperature_users, || {
+            let was_off = !T::regs().ccr().read().tsen();
+            T::regs().ccr().modify(|reg| reg.set_tsen(true));
+            was_off
+        });
+        (Self(PhantomData), was_off)
+    }
+}
+impl<T: Instance> InternalChannel<T> for Temperature<T> {
+    fn name(&self) -> &'static str {
+        "temperature"
+    }
+}
+impl<T: Instance> super::sealed::InternalChannel<T> for Temperature<T> {
     fn channel(&self) -> u8 {
-        17
+        TEMPERATURE_CHANNEL
+    }
+}
+impl<T: Instance> Drop for Temperature<T> {
+    fn drop(&mut self) {
+        release_internal(&T::state().temperature_users, || {
+            T::regs().ccr().modify(|reg| reg.set_tsen(false))
+        });
     }
 }
 
-pub struct Temperature;
-impl InternalChannel<ADC> for Temperature {}
-impl super::sealed::InternalChannel<ADC> for Temperature {
+/// The LCD supply voltage, on parts with an LCD controller.
+#[cfg(stm32l0)]
+pub struct Vlcd;
+#[cfg(stm32l0)]
+impl<T: Instance> InternalChannel<T> for Vlcd {
+    fn name(&self) -> &'static str {
+        "vlcd"
+    }
+}
+#[cfg(stm32l0)]
+impl<T: Instance> super::sealed::InternalChannel<T> for Vlcd {
     fn channel(&self) -> u8 {
-        16
+        VLCD_CHANNEL
     }
 }
 
-impl<'d, T: Instance> Adc<'d, T> {
-    pub fn new(
-        adc: impl Peripheral<P = T> + 'd,
-        _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'd,
-        delay: &mut impl DelayUs<u32>,
-    ) -> Self {
-        into_ref!(adc);
-        T::enable();
-        T::reset();
+/// Compile-time check of the capacity of [`Adc::read_sequence_owned`].
+struct SequenceCapacity<const N: usize>;
 
-        // Delay 1μs when using HSI14 as the ADC clock.
-        //
-        // Table 57. ADC characteristics
-        // tstab = 14 * 1/fadc
-        delay.delay_us(1);
+impl<const N: usize> SequenceCapacity<N> {
+    const CHECK: () = assert!(N <= CHANNEL_COUNT, "capacity exceeds the number of ADC channels");
+}
 
-        // A.7.1 ADC calibration code example
-        T::regs().cfgr1().modify(|reg| reg.set_dmaen(false));
-        T::regs().cr().modify(|reg| reg.set_adcal(true));
-        while T::regs().cr().read().adcal() {}
+/// A validated set of channels for [`Adc::read_sequence`].
+///
+/// The ADC converts every selected channel once per scan, in ascending order, so a sequence can't
+/// contain a channel twice. `new` is a `const fn`, so a fixed sequence can be checked at compile
+/// time:
+///
+/// ```ignore
+/// const SEQUENCE: AdcSequence = match AdcSequence::new(&[1, 4, 17]) {
+///     Ok(sequence) => sequence,
+///     Err(_) => panic!("invalid ADC sequence"),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AdcSequence {
+    /// Bit n is set if channel n is part of the sequence, like in `chselr`.
+    mask: u32,
+}
 
-        // A.7.2 ADC enable sequence code example
-        if T::regs().isr().read().adrdy() {
-            T::regs().isr().modify(|reg| reg.set_adrdy(true));
-        }
-        T::regs().cr().modify(|reg| reg.set_aden(true));
-        while !T::regs().isr().read().adrdy() {
-            // ES0233, 2.4.3 ADEN bit cannot be set immediately after the ADC calibration
-            // Workaround: When the ADC calibration is complete (ADCAL = 0), keep setting the
-            // ADEN bit until the ADRDY flag goes high.
-            T::regs().cr().modify(|reg| reg.set_aden(true));
+impl AdcSequence {
+    /// Check `channels`, failing with [`AdcError::InvalidChannel`] for a channel the ADC doesn't
+    /// have and [`AdcError::DuplicateChannel`] for one given twice.
+    pub const fn new(channels: &[u8]) -> Result<Self, AdcError> {
+        let mut mask = 0u32;
+        let mut i = 0;
+        while i < channels.len() {
+            let channel = channels[i];
+            if channel as usize >= CHANNEL_COUNT {
+                return Err(AdcError::InvalidChannel);
+            }
+            if mask & (1 << channel) != 0 {
+                return Err(AdcError::DuplicateChannel);
+            }
+            mask |= 1 << channel;
+            i += 1;
         }
 
-        T::Interrupt::unpend();
-        unsafe {
-            T::Interrupt::enable();
-        }
+        Ok(Self { mask })
+    }
 
-        Self {
-            adc,
-            sample_time: Default::default(),
-        }
+    /// Number of channels in the sequence.
+    pub const fn len(&self) -> usize {
+        self.mask.count_ones() as usize
     }
 
-    pub fn enable_vbat(&self, _delay: &mut impl DelayUs<u32>) -> Vbat {
-        // SMP must be ≥ 56 ADC clock cycles when using HSI14.
-        //
-        // 6.3.20 Vbat monitoring characteristics
-        // ts_vbat ≥ 4μs
-        T::regs().ccr().modify(|reg| reg.set_vbaten(true));
-        Vbat
+    pub const fn is_empty(&self) -> bool {
+        self.mask == 0
     }
 
-    pub fn enable_vref(&self, delay: &mut impl DelayUs<u32>) -> Vref {
-        // Table 28. Embedded internal reference voltage
-        // tstart = 10μs
-        T::regs().ccr().modify(|reg| reg.set_vrefen(true));
-        delay.delay_us(10);
-        Vref
+    /// The channels in the order they're converted in.
+    pub fn channels(&self) -> impl Iterator<Item = u8> {
+        let mask = self.mask;
+        (0..CHANNEL_COUNT as u8).filter(move |&channel| mask & (1 << channel) != 0)
     }
+}
 
-    pub fn enable_temperature(&self, delay: &mut impl DelayUs<u32>) -> Temperature {
-        // SMP must be ≥ 56 ADC clock cycles when using HSI14.
-        //
-        // 6.3.19 Temperature sensor characteristics
-        // tstart ≤ 10μs
-        // ts_temp ≥ 4μs
-        T::regs().ccr().modify(|reg| reg.set_tsen(true));
-        delay.delay_us(10);
-        Temperature
+/// An external or internal channel, for code that handles both kinds the same way, see
+/// [`Adc::read_channel_enum`].
+pub enum AdcChannel<'a, T: Instance> {
+    /// An external channel, through its pin.
+    Pin(&'a mut dyn AdcPin<T>),
+    /// An internal channel, by its number.
+    Internal(u8),
+}
+
+/// Catch channel numbers the ADC doesn't have in debug builds, with a clearer message than the
+/// out of bounds panics further down.
+fn debug_check_channel(channel: u8) {
+    debug_assert!(
+        (channel as usize) < CHANNEL_COUNT,
+        "ADC channel {} doesn't exist, the ADC only has {} channels",
+        channel,
+        CHANNEL_COUNT
+    );
+}
+
+/// Run the calibration, with the ADC disabled, and return the calibration factor.
+///
+/// Fails with [`AdcError::Timeout`] if `ADCAL` isn't cleared within [`DROP_SPIN_LIMIT`] polls,
+/// e.g. because the ADC clock isn't running.
+fn run_calibration(regs: crate::pac::adc::Adc) -> Result<u8, AdcError> {
+    start_calibration(regs);
+    if !spin_until(|| !regs.cr().read().adcal()) {
+        return Err(AdcError::Timeout);
     }
+    Ok(read_calibration_factor(regs))
+}
 
-    pub fn set_sample_time(&mut self, sample_time: SampleTime) {
-        self.sample_time = sample_time;
+/// Like [`run_calibration`], but lets other tasks run while the calibration is in progress.
+#[cfg(feature = "time")]
+async fn run_calibration_async(regs: crate::pac::adc::Adc) -> Result<u8, AdcError> {
+    start_calibration(regs);
+    for _ in 0..DROP_SPIN_LIMIT {
+        if !regs.cr().read().adcal() {
+            return Ok(read_calibration_factor(regs));
+        }
+        embassy_futures::yield_now().await;
     }
+    Err(AdcError::Timeout)
+}
 
-    pub fn set_resolution(&mut self, resolution: Resolution) {
-        T::regs().cfgr1().modify(|reg| reg.set_res(resolution.into()));
+fn start_calibration(regs: crate::pac::adc::Adc) {
+    // A.7.1 ADC calibration code example
+    regs.cfgr1().modify(|reg| reg.set_dmaen(false));
+    regs.cr().modify(|reg| reg.set_adcal(true));
+}
+
+/// The calibration factor right after a calibration.
+fn read_calibration_factor(regs: crate::pac::adc::Adc) -> u8 {
+    // The factor is left in DR[6:0] until the first conversion.
+    #[cfg(not(stm32l0))]
+    return regs.dr().read().data() as u8 & 0x7F;
+    #[cfg(stm32l0)]
+    return regs.calfact().read().calfact();
+}
+
+/// How many times the ADC is enabled again before giving up on ADRDY.
+///
+/// The ADC is normally ready within a few microseconds, while this many retries take several
+/// milliseconds.
+const ENABLE_RETRY_LIMIT: u32 = 100_000;
+
+/// Enable the ADC and wait for it to become ready, failing with [`AdcError::EnableFailed`] if it
+/// isn't after [`ENABLE_RETRY_LIMIT`] retries.
+fn try_enable_adc(regs: crate::pac::adc::Adc) -> Result<(), AdcError> {
+    // A.7.2 ADC enable sequence code example
+    if regs.isr().read().adrdy() {
+        regs.isr().modify(|reg| reg.set_adrdy(true));
     }
+    regs.cr().modify(|reg| reg.set_aden(true));
 
-    pub async fn read<P>(&mut self, pin: &mut P) -> u16
-    where
-        P: AdcPin<T> + crate::gpio::sealed::Pin,
-    {
-        let channel = pin.channel();
-        pin.set_as_analog();
-        self.read_channel(channel).await
+    // In auto-off mode the ADC powers itself up for each conversion, so ADRDY can't be relied on.
+    if regs.cfgr1().read().autoff() {
+        return Ok(());
     }
 
-    pub async fn read_internal(&mut self, channel: &mut impl InternalChannel<T>) -> u16 {
-        let channel = channel.channel();
-        self.read_channel(channel).await
+    for _ in 0..ENABLE_RETRY_LIMIT {
+        if regs.isr().read().adrdy() {
+            return Ok(());
+        }
+        // ES0233, 2.4.3 ADEN bit cannot be set immediately after the ADC calibration
+        // Workaround: When the ADC calibration is complete (ADCAL = 0), keep setting the
+        // ADEN bit until the ADRDY flag goes high.
+        regs.cr().modify(|reg| reg.set_aden(true));
     }
 
-    async fn convert(&mut self) -> u16 {
-        T::regs().isr().modify(|reg| {
-            reg.set_eoc(true);
-            reg.set_eosmp(true);
-        });
+    Err(AdcError::EnableFailed)
+}
 
-        T::regs().smpr().modify(|reg| reg.set_smp(self.sample_time.into()));
-        T::regs().ier().modify(|w| w.set_eocie(true));
-        T::regs().cr().modify(|reg| reg.set_adstart(true));
+/// Stop any conversion and disable the ADC.
+fn disable_adc(regs: crate::pac::adc::Adc) {
+    // A.7.3 ADC disable code example
+    stop_conversion(regs);
+    if regs.cr().read().aden() {
+        regs.cr().modify(|reg| reg.set_addis(true));
+        if !spin_until(|| !regs.cr().read().aden()) {
+            warn!("ADC didn't acknowledge being disabled");
+        }
+    }
+}
 
-        poll_fn(|cx| {
-            T::state().waker.register(cx.waker());
+/// Stop the ongoing conversion, if any.
+///
+/// `cfgr1` and `chselr` must not be written while a conversion is running.
+///
+/// Without an ADC clock, e.g. when a read timed out because HSI14 was turned off, the ADC never
+/// acknowledges ADSTP. The wait is bounded, and the ADC is disabled instead if it runs out, so
+/// reads fail with [`AdcError::Disabled`] until it's enabled again.
+fn stop_conversion(regs: crate::pac::adc::Adc) {
+    if !regs.cr().read().adstart() {
+        return;
+    }
 
-            if T::regs().isr().read().eoc() {
-                Poll::Ready(())
-            } else {
-                Poll::Pending
-            }
-        })
-        .await;
+    regs.cr().modify(|reg| reg.set_adstp(true));
+    if spin_until(|| !regs.cr().read().adstp()) {
+        return;
+    }
 
-        T::regs().dr().read().data()
+    warn!("ADC didn't stop converting, disabling it");
+    regs.cr().modify(|reg| reg.set_addis(true));
+    if !spin_until(|| !regs.cr().read().aden()) {
+        warn!("ADC didn't acknowledge being disabled");
     }
+}
 
-    async fn read_channel(&mut self, channel: u8) -> u16 {
-        // A.7.5 Single conversion sequence code example - Software trigger
-        T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+/// End a single conversion or sequence whose result has been read.
+///
+/// With a hardware trigger, ADSTART stays set afterwards, the ADC waiting for the next trigger, so
+/// `chselr` and `smpr` couldn't be written for the next read. Stop it then. With software
+/// triggering, the ADC already cleared ADSTART by itself.
+fn end_conversion(regs: crate::pac::adc::Adc) {
+    if regs.cfgr1().read().exten() != Exten::DISABLED {
+        stop_conversion(regs);
+    }
+}
+
+/// Abort a single conversion nobody waits for anymore, e.g. after a timeout.
+///
+/// Like [`stop_conversion`], this doesn't hang if the ADC doesn't acknowledge the abort.
+fn abort_conversion(regs: crate::pac::adc::Adc) {
+    stop_conversion(regs);
+    regs.ier().modify(|w| {
+        w.set_eocie(false);
+        w.set_ovrie(false);
+    });
+    // Nobody reads `dr` anymore, so clear the flags here for them not to fire as soon as
+    // their interrupt is enabled again.
+    regs.isr().write(|reg| {
+        reg.set_eoc(true);
+        reg.set_eosmp(true);
+        reg.set_ovr(true);
+    });
+}
 
-        self.convert().await
+/// Whether the asynchronous ADC clock is running. On the STM32F0, HSI14 is started first if it
+/// isn't yet.
+fn async_clock_ready() -> bool {
+    // With HSI14DIS clear the ADC could request HSI14 by itself, but only once it's enabled, so
+    // that doesn't tell whether the clock works. Start it and wait for HSI14RDY instead.
+    #[cfg(stm32f0)]
+    {
+        let rcc = crate::pac::RCC;
+        if !rcc.cr2().read().hsi14rdy() {
+            rcc.cr2().modify(|w| w.set_hsi14on(true));
+        }
+        spin_until(|| rcc.cr2().read().hsi14rdy())
+    }
+    #[cfg(stm32l0)]
+    {
+        crate::pac::RCC.cr().read().hsi16rdyf()
     }
 }
 
-impl<'d, T: Instance> Drop for Adc<'d, T> {
-    fn drop(&mut self) {
-        // A.7.3 ADC disable code example
-        T::regs().cr().modify(|reg| reg.set_adstp(true));
-        while T::regs().cr().read().adstp() {}
+/// DMA options for the scans: transfer errors are reported as [`AdcError::Dma`] rather than
+/// panicking in the DMA interrupt handler.
+fn dma_options() -> TransferOptions {
+    TransferOptions {
+        report_transfer_error: true,
+        ..Default::default()
+    }
+}
 
-        T::regs().cr().modify(|reg| reg.set_addis(true));
-        while T::regs().cr().read().aden() {}
+/// Mark the ADC as in use by a driver, panicking in debug builds if it already is.
+///
+/// Owning the peripheral rules this out, but `steal` and other unsafe code can get around that.
+/// A second driver would reset and recalibrate the ADC under the first one.
+fn claim<T: Instance>() {
+    let claimed = T::state().claimed.swap(true, Ordering::Relaxed);
+    debug_assert!(!claimed, "ADC is already in use by another driver");
+}
+
+/// Enable the ADC interrupt. Only call this when the caller holds the interrupt binding.
+fn enable_interrupt<T: Instance>() {
+    T::Interrupt::unpend();
+    unsafe {
+        T::Interrupt::enable();
+    }
+}
+
+impl<'d, T: Instance> Adc<'d, T> {
+    /// Create a new ADC driver with the default configuration.
+    ///
+    /// The ADC is calibrated and converts at 12 bits, with a sample time of
+    /// [`SampleTime::Cycles1_5`]: see [`set_sample_time`](Self::set_sample_time) for when that's
+    /// too short.
+    pub fn new(
+        adc: impl Peripheral<P = T> + 'd,
+        irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+        delay: &mut impl DelayUs<u32>,
+    ) -> Self {
+        Self::new_with(adc, irq, delay, Default::default())
+    }
+
+    /// Start building an ADC driver with a custom configuration.
+    ///
+    /// The configuration is applied before the ADC is enabled, like with
+    /// [`new_with`](Self::new_with).
+    pub fn builder(
+        adc: impl Peripheral<P = T> + 'd,
+        _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> AdcBuilder<'d, T> {
+        into_ref!(adc);
+        AdcBuilder {
+            adc,
+            config: Default::default(),
+        }
+    }
+
+    /// Create a new ADC driver with the given configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ADC clock isn't running or the ADC doesn't become ready, see
+    /// [`try_new_with`](Self::try_new_with).
+    pub fn new_with(
+        adc: impl Peripheral<P = T> + 'd,
+        irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+        delay: &mut impl DelayUs<u32>,
+        config: AdcConfig,
+    ) -> Self {
+        Self::try_new_with(adc, irq, delay, config).expect("ADC failed to start")
+    }
+
+    /// Create a new ADC driver with the given configuration, failing with
+    /// [`AdcError::ClockNotReady`] if the asynchronous ADC clock isn't running.
+    ///
+    /// Without the clock the calibration would never complete, and [`new`](Self::new) would hang.
+    /// Fails with [`AdcError::Timeout`] if the calibration doesn't complete anyway, and with
+    /// [`AdcError::EnableFailed`] if the ADC doesn't become ready after being enabled.
+    pub fn try_new_with(
+        adc: impl Peripheral<P = T> + 'd,
+        _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+        delay: &mut impl DelayUs<u32>,
+        config: AdcConfig,
+    ) -> Result<Self, AdcError> {
+        let this = Self::new_inner(adc, delay, config)?;
+        enable_interrupt::<T>();
+        Ok(this)
+    }
+
+    /// Create a new ADC driver without an interrupt, for firmware that only does busy-polling
+    /// reads.
+    ///
+    /// No `bind_interrupts!` is needed. The returned [`BlockingAdc`] only has the blocking reads,
+    /// since the interrupt-driven async ones would never be woken up, and
+    /// [`read_polled`](BlockingAdc::read_polled).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ADC clock isn't running or the ADC doesn't become ready, like
+    /// [`new_with`](Self::new_with).
+    pub fn new_blocking(adc: impl Peripheral<P = T> + 'd, delay: &mut impl DelayUs<u32>) -> BlockingAdc<'d, T> {
+        BlockingAdc {
+            adc: Self::new_inner(adc, delay, Default::default()).expect("ADC failed to start"),
+            interrupt_enabled: false,
+        }
+    }
+
+    fn new_inner(
+        adc: impl Peripheral<P = T> + 'd,
+        delay: &mut impl DelayUs<u32>,
+        config: AdcConfig,
+    ) -> Result<Self, AdcError> {
+        into_ref!(adc);
+        claim::<T>();
+        T::enable();
+        T::reset();
+
+        // CKMODE can only be written while the ADC is disabled, and the calibration runs on it.
+        T::regs()
+            .cfgr2()
+            .modify(|reg| reg.set_ckmode(config.clock_source.into()));
+        if config.clock_source == AdcClockSource::Async && !async_clock_ready() {
+            T::disable();
+            T::state().claimed.store(false, Ordering::Relaxed);
+            return Err(AdcError::ClockNotReady);
+        }
+
+        // Delay 1μs when using HSI14 as the ADC clock.
+        //
+        // Table 57. ADC characteristics
+        // tstab = 14 * 1/fadc
+        delay.delay_us(1);
+
+        let calibration_factor = if config.calibrate {
+            match run_calibration(T::regs()) {
+                Ok(factor) => factor,
+                Err(e) => {
+                    T::disable();
+                    T::state().claimed.store(false, Ordering::Relaxed);
+                    return Err(e);
+                }
+            }
+        } else {
+            #[cfg(not(stm32l0))]
+            let calibration_factor = 0;
+            #[cfg(stm32l0)]
+            let calibration_factor = T::regs().calfact().read().calfact();
+            calibration_factor
+        };
+
+        Self::init(adc, config, calibration_factor)
+    }
+
+    /// Create a new ADC driver, waiting for the ADC to stabilize and calibrate without blocking the
+    /// executor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ADC clock isn't running or the ADC doesn't become ready, like
+    /// [`new_with`](Self::new_with).
+    #[cfg(feature = "time")]
+    pub async fn new_async(
+        adc: impl Peripheral<P = T> + 'd,
+        _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Self {
+        into_ref!(adc);
+        // Check the clock before claiming the ADC, so the panic doesn't leave it claimed.
+        assert!(async_clock_ready(), "ADC clock is not running");
+        claim::<T>();
+        T::enable();
+        T::reset();
+
+        // See `new` for the stabilization time.
+        Timer::after(Duration::from_micros(1)).await;
+
+        let calibration_factor = match run_calibration_async(T::regs()).await {
+            Ok(factor) => factor,
+            Err(_) => {
+                T::disable();
+                T::state().claimed.store(false, Ordering::Relaxed);
+                panic!("ADC calibration didn't complete");
+            }
+        };
+
+        let this = Self::init(adc, Default::default(), calibration_factor).expect("ADC failed to start");
+        enable_interrupt::<T>();
+        this
+    }
+
+    /// Create a driver for an ADC that has already been set up, e.g. by a bootloader.
+    ///
+    /// Unlike [`new`](Self::new), this doesn't reset, calibrate or enable the ADC, so conversions
+    /// of the previous owner aren't glitched. The configuration in the registers, like the
+    /// resolution and clock source, is kept. The sample time set with
+    /// [`set_sample_time`](Self::set_sample_time) starts out as the default, and
+    /// [`calibration_factor`](Self::calibration_factor) returns zero on the STM32F0, where the factor
+    /// can't be read back.
+    ///
+    /// # Safety
+    ///
+    /// The ADC must not be used by anything else anymore, including DMA transfers or interrupt
+    /// handlers set up by the previous owner.
+    ///
+    /// In debug builds, this panics if another [`Adc`] for the same ADC still exists.
+    pub unsafe fn steal(
+        adc: impl Peripheral<P = T> + 'd,
+        _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Self {
+        into_ref!(adc);
+        claim::<T>();
+        // Only makes sure the clock is running, there's no reset.
+        T::enable();
+
+        #[cfg(not(stm32l0))]
+        let calibration_factor = 0;
+        #[cfg(stm32l0)]
+        let calibration_factor = T::regs().calfact().read().calfact();
+
+        enable_interrupt::<T>();
+
+        Self {
+            adc,
+            sample_time: Default::default(),
+            channel_sample_times: [None; CHANNEL_COUNT],
+            calibration_factor,
+            pending_channel: None,
+            vdda_calib_mv: VDDA_CALIB_MV,
+            correction: NO_CORRECTION,
+        }
+    }
+
+    /// Configure and enable the ADC once it has stabilized and been calibrated.
+    fn init(adc: PeripheralRef<'d, T>, config: AdcConfig, calibration_factor: u8) -> Result<Self, AdcError> {
+        // RES can only be written while the ADC is disabled.
+        T::regs().cfgr1().modify(|reg| reg.set_res(config.resolution.into()));
+        T::regs().smpr().modify(|reg| reg.set_smp(config.sample_time.into()));
+
+        let this = Self {
+            adc,
+            sample_time: config.sample_time,
+            channel_sample_times: [None; CHANNEL_COUNT],
+            calibration_factor,
+            pending_channel: None,
+            vdda_calib_mv: VDDA_CALIB_MV,
+            correction: NO_CORRECTION,
+        };
+        // On failure, dropping `this` disables the ADC and its clock again.
+        try_enable_adc(T::regs())?;
+
+        Ok(this)
+    }
+
+    /// Recalibrate the ADC.
+    ///
+    /// The reference manual recommends this after long idle periods or large changes of VDDA.
+    /// The ADC is disabled for the calibration and left enabled and ready afterwards. Returns the
+    /// new calibration factor, or [`AdcError::EnableFailed`] if the ADC doesn't become ready again.
+    /// Fails with [`AdcError::Timeout`] if the calibration doesn't complete, e.g. because the ADC
+    /// clock isn't running; the ADC is left disabled then.
+    pub fn calibrate(&mut self) -> Result<u8, AdcError> {
+        self.abort_pending();
+        disable_adc(T::regs());
+        self.calibration_factor = run_calibration(T::regs())?;
+        try_enable_adc(T::regs())?;
+
+        Ok(self.calibration_factor)
+    }
+
+    /// Disable the ADC to save power, without releasing it.
+    ///
+    /// The calibration is kept, so [`enable`](Self::enable) brings the ADC back quickly. Reads
+    /// fail with [`AdcError::Disabled`] until then.
+    pub fn disable(&mut self) {
+        self.abort_pending();
+        disable_adc(T::regs());
+    }
+
+    /// Enable the ADC again after [`disable`](Self::disable).
+    ///
+    /// Fails with [`AdcError::EnableFailed`] if the ADC doesn't become ready, e.g. because its
+    /// clock isn't running.
+    pub fn enable(&mut self) -> Result<(), AdcError> {
+        try_enable_adc(T::regs())
+    }
+
+    /// Disable the ADC and give back the peripheral, e.g. to create a differently configured
+    /// driver for it later.
+    ///
+    /// This is what dropping the driver does, returning the peripheral instead: a running
+    /// conversion is aborted, the ADC is disabled and its peripheral clock stopped. Waiting for the
+    /// ADC to acknowledge the stop and the disable is bounded, so this doesn't hang if the ADC clock
+    /// was turned off. The ADC interrupts are switched off too. A new driver resets the ADC, so
+    /// nothing is left over from this one.
+    pub fn free(self) -> PeripheralRef<'d, T> {
+        T::regs().ier().write(|_| {});
+        shut_down::<T>();
+
+        let this = core::mem::ManuallyDrop::new(self);
+        // Safety: `this` is never used or dropped again, and the other fields don't need dropping.
+        unsafe { core::ptr::read(&this.adc) }
+    }
+
+    /// Disable the ADC before entering a low-power mode, saving its calibration factor.
+    ///
+    /// Pass the token to [`resume`](Self::resume) on wake-up, which re-enables the ADC without
+    /// running the calibration again.
+    pub fn suspend(&mut self) -> CalibrationToken {
+        self.abort_pending();
+        stop_conversion(T::regs());
+        disable_adc(T::regs());
+
+        CalibrationToken {
+            factor: self.calibration_factor,
+        }
+    }
+
+    /// Re-enable the ADC after [`suspend`](Self::suspend), restoring the saved calibration factor.
+    ///
+    /// On the STM32L0 the factor is written back to `CALFACT`. The STM32F0 can't write the factor,
+    /// but keeps it in the ADC as long as the ADC is powered, which is the case in Stop mode. In
+    /// Standby mode, or after removing VDDA, [`calibrate`](Self::calibrate) has to be used instead.
+    ///
+    /// Fails with [`AdcError::EnableFailed`] if the ADC doesn't become ready, like
+    /// [`enable`](Self::enable).
+    pub fn resume(&mut self, token: CalibrationToken) -> Result<(), AdcError> {
+        try_enable_adc(T::regs())?;
+
+        // CALFACT can only be written while the ADC is enabled and idle.
+        #[cfg(stm32l0)]
+        T::regs().calfact().modify(|reg| reg.set_calfact(token.factor));
+        self.calibration_factor = token.factor;
+
+        Ok(())
+    }
+
+    fn check_enabled() -> Result<(), AdcError> {
+        if T::regs().cr().read().aden() {
+            Ok(())
+        } else {
+            Err(AdcError::Disabled)
+        }
+    }
+
+    /// Access the ADC registers directly, for features the driver doesn't support yet.
+    ///
+    /// # Safety
+    ///
+    /// The driver caches part of the configuration, like the sample times and the calibration
+    /// factor, and relies on the registers being in the state it left them in: e.g. with `ADSTART`
+    /// clear and `DMAEN` only set during DMA reads. Writing the registers can break these
+    /// assumptions. Don't change anything the driver manages, and leave the ADC idle before
+    /// calling any other method.
+    #[cfg(feature = "unstable-pac")]
+    pub unsafe fn regs(&self) -> crate::pac::adc::Adc {
+        T::regs()
+    }
+
+    /// Read the state of the ADC, without side effects.
+    pub fn status(&self) -> AdcStatus {
+        let isr = T::regs().isr().read();
+        let cr = T::regs().cr().read();
+
+        AdcStatus {
+            ready: isr.adrdy(),
+            overrun: isr.ovr(),
+            calibrating: cr.adcal(),
+            converting: cr.adstart(),
+        }
+    }
+
+    /// Read all ADC registers, e.g. for logging them in a bug report.
+    ///
+    /// This only reads registers without read side effects, so no flags are cleared. `DR` is left
+    /// out for that reason, reading it clears `EOC`.
+    pub fn dump_registers(&self) -> AdcRegDump {
+        let regs = T::regs();
+
+        AdcRegDump {
+            isr: regs.isr().read().0,
+            ier: regs.ier().read().0,
+            cr: regs.cr().read().0,
+            cfgr1: regs.cfgr1().read().0,
+            cfgr2: regs.cfgr2().read().0,
+            smpr: regs.smpr().read().0,
+            chselr: regs.chselr().read().0,
+            ccr: regs.ccr().read().0,
+            #[cfg(stm32l0)]
+            calfact: regs.calfact().read().0,
+        }
+    }
+
+    /// The calibration factor found by the last calibration.
+    pub fn calibration_factor(&self) -> u8 {
+        self.calibration_factor
+    }
+
+    /// Connect the VBAT divider.
+    ///
+    /// Dropping the last [`Vbat`] disconnects it again, so it no longer draws current from the
+    /// battery.
+    #[cfg(not(stm32l0))]
+    pub fn enable_vbat(&self, _delay: &mut impl DelayUs<u32>) -> Vbat<T> {
+        // SMP must be ≥ 56 ADC clock cycles when using HSI14.
+        //
+        // 6.3.20 Vbat monitoring characteristics
+        // ts_vbat ≥ 4μs
+        Vbat::acquire()
+    }
+
+    /// Disconnect the VBAT divider again unless another [`Vbat`] is alive, which is the same as
+    /// dropping `vbat`.
+    #[cfg(not(stm32l0))]
+    pub fn disable_vbat(&self, vbat: Vbat<T>) {
+        drop(vbat);
+    }
+
+    /// Enable the internal voltage reference and wait for it to start up.
+    ///
+    /// If it's already running, this returns right away. The markers are counted: the reference
+    /// is turned off again once the last [`Vref`] is dropped.
+    pub fn enable_vref(&self, delay: &mut impl DelayUs<u32>) -> Vref<T> {
+        let (vref, was_off) = Vref::acquire();
+
+        // Table 28. Embedded internal reference voltage
+        // tstart = 10μs
+        if was_off {
+            delay.delay_us(10);
+        }
+        vref
+    }
+
+    /// Like [`enable_vref`](Self::enable_vref), but waits for the reference to start up asynchronously.
+    #[cfg(feature = "time")]
+    pub async fn enable_vref_async(&self) -> Vref<T> {
+        let (vref, was_off) = Vref::acquire();
+        if was_off {
+            Timer::after(Duration::from_micros(10)).await;
+        }
+        vref
+    }
+
+    /// Turn the internal reference off again to save power unless another [`Vref`] is alive, which
+    /// is the same as dropping `vref`.
+    pub fn disable_vref(&self, vref: Vref<T>) {
+        drop(vref);
+    }
+
+    /// Enable the temperature sensor and wait for it to start up.
+    ///
+    /// If it's already running, this returns right away. The markers are counted: the sensor is
+    /// turned off again once the last [`Temperature`] is dropped.
+    pub fn enable_temperature(&self, delay: &mut impl DelayUs<u32>) -> Temperature<T> {
+        let (temperature, was_off) = Temperature::acquire();
+
+        // SMP must be ≥ 56 ADC clock cycles when using HSI14.
+        //
+        // 6.3.19 Temperature sensor characteristics
+        // tstart ≤ 10μs
+        // ts_temp ≥ 4μs
+        if was_off {
+            delay.delay_us(10);
+        }
+        temperature
+    }
+
+    /// Like [`enable_temperature`](Self::enable_temperature), but waits for the sensor to start up
+    /// asynchronously.
+    #[cfg(feature = "time")]
+    pub async fn enable_temperature_async(&self) -> Temperature<T> {
+        let (temperature, was_off) = Temperature::acquire();
+        if was_off {
+            Timer::after(Duration::from_micros(10)).await;
+        }
+        temperature
+    }
+
+    /// Turn the temperature sensor off again to save power unless another [`Temperature`] is alive,
+    /// which is the same as dropping `temperature`.
+    pub fn disable_temperature(&self, temperature: Temperature<T>) {
+        drop(temperature);
+    }
+
+    /// Get the LCD supply voltage channel.
+    ///
+    /// The channel is connected directly to VLCD, so there is nothing to enable or wait for on the
+    /// ADC side. The LCD controller has to be running for it to read anything useful.
+    #[cfg(stm32l0)]
+    pub fn enable_vlcd(&self) -> Vlcd {
+        Vlcd
+    }
+
+    /// Convert a temperature sensor sample to degrees Celsius.
+    ///
+    /// Uses the two factory calibration points of the temperature sensor. `vref_sample` is a
+    /// [`Vref`] reading taken close to `raw`, and is used to compensate for VDDA being different
+    /// from the voltage the calibration values were measured at. Both samples must be taken at
+    /// 12-bit resolution.
+    #[cfg(any(all(stm32f0, not(stm32f0x0)), stm32l0))]
+    pub fn temperature_to_celsius(&self, raw: u16, vref_sample: u16) -> f32 {
+        let vrefint_cal = calibration::read(calibration::VREFINT_CAL) as f32;
+
+        // Scale the sample to what it would have read at the calibration VDDA.
+        Self::calibrated_temperature(raw as f32 * vrefint_cal / vref_sample as f32)
+    }
+
+    /// Convert a temperature sensor sample to degrees Celsius, given the VDDA it was measured
+    /// against.
+    ///
+    /// Like [`temperature_to_celsius`](Self::temperature_to_celsius), but for a VDDA measured once
+    /// beforehand, e.g. with [`read_vdda_mv`](Self::read_vdda_mv), instead of a fresh [`Vref`]
+    /// sample for every reading. The sample must be taken at 12-bit resolution.
+    #[cfg(any(all(stm32f0, not(stm32f0x0)), stm32l0))]
+    pub fn temperature_to_celsius_with_vdda(&self, raw: u16, vdda_mv: u32) -> f32 {
+        Self::calibrated_temperature(raw as f32 * vdda_mv as f32 / self.vdda_calib_mv as f32)
+    }
+
+    /// Interpolate between the factory calibration points, for a sample at the calibration VDDA.
+    #[cfg(any(all(stm32f0, not(stm32f0x0)), stm32l0))]
+    fn calibrated_temperature(raw: f32) -> f32 {
+        use calibration::{TS_CAL1_TEMP, TS_CAL2_TEMP};
+
+        let ts_cal1 = calibration::read(calibration::TS_CAL1) as f32;
+        let ts_cal2 = calibration::read(calibration::TS_CAL2) as f32;
+
+        (raw - ts_cal1) * (TS_CAL2_TEMP - TS_CAL1_TEMP) / (ts_cal2 - ts_cal1) + TS_CAL1_TEMP
+    }
+
+    /// Check that the temperature sensor reads something plausible.
+    ///
+    /// Reads the temperature sensor and the internal reference, and fails with
+    /// [`AdcError::Implausible`] if the resulting temperature is outside of the operating range
+    /// of the chip. This catches most setup mistakes, like a too slow or misconfigured ADC clock,
+    /// or broken factory calibration values. Readings are expected to be right-aligned.
+    #[cfg(any(all(stm32f0, not(stm32f0x0)), stm32l0))]
+    pub async fn temperature_sanity_check(
+        &mut self,
+        temperature: &mut Temperature<T>,
+        vref: &mut Vref<T>,
+    ) -> Result<(), AdcError> {
+        // The calibration values are 12-bit readings.
+        let shift = 12 - Resolution::from(T::regs().cfgr1().read().res()).bits();
+        let raw = self.read_internal(temperature).await? << shift;
+        let vref_sample = self.read_internal(vref).await? << shift;
+
+        // Also rejects a zero `vref_sample`, which gives an infinite or NaN temperature.
+        if !PLAUSIBLE_TEMPERATURE.contains(&self.temperature_to_celsius(raw, vref_sample)) {
+            return Err(AdcError::Implausible);
+        }
+
+        Ok(())
+    }
+
+    /// Number of channels of the ADC, including the internal ones.
+    ///
+    /// This is the number of channels `CHSELR` can select, which is the same for all parts using
+    /// this ADC version. Smaller packages don't bond out all external channels.
+    pub const fn channel_count() -> u8 {
+        CHANNEL_COUNT as u8
+    }
+
+    /// Whether `channel` is connected to an internal source, like [`Vref`], rather than a pin.
+    pub const fn channel_is_internal(channel: u8) -> bool {
+        channel >= FIRST_INTERNAL_CHANNEL && (channel as usize) < CHANNEL_COUNT
+    }
+
+    /// Typical internal reference voltage in millivolts for the family being built for.
+    pub const fn vref_int_typical() -> u32 {
+        VREF_INT_TYPICAL_MV
+    }
+
+    /// Compute the actual VDDA in millivolts from a [`Vref`] sample.
+    ///
+    /// This uses the factory `VREFINT_CAL` value, which is much more accurate than the typical
+    /// [`Adc::vref_int_typical`]. The sample must be taken at 12-bit resolution.
+    #[cfg(any(stm32f0, stm32l0))]
+    pub fn vdda_mv(&self, vrefint_sample: u16) -> u32 {
+        let vrefint_cal = calibration::read(calibration::VREFINT_CAL) as u32;
+        self.vdda_calib_mv * vrefint_cal / vrefint_sample as u32
+    }
+
+    /// Measure VDDA in millivolts.
+    ///
+    /// This enables [`Vref`] and waits for it to start up if needed, reads it, and computes VDDA
+    /// from the factory calibration like [`vdda_mv`](Self::vdda_mv). The sample is scaled to 12
+    /// bits, so any resolution and alignment can be used. If no [`Vref`] is held elsewhere, the
+    /// reference is disabled again afterwards to save power.
+    #[cfg(all(feature = "time", any(stm32f0, stm32l0)))]
+    pub async fn read_vdda_mv(&mut self) -> Result<u32, AdcError> {
+        let mut vref = self.enable_vref_async().await;
+        let sample = self.read_internal(&mut vref).await;
+        // This only turns it off if nobody else holds a `Vref`.
+        self.disable_vref(vref);
+
+        let sample_12bit = sample? as u32 * Resolution::TwelveBit.to_max_count() / Self::max_count();
+        Ok(self.vdda_mv(sample_12bit as u16))
+    }
+
+    /// Measure the battery voltage on VBAT in millivolts.
+    ///
+    /// This reads [`Vbat`], enabling it for the read if needed, measures VDDA with
+    /// [`read_vdda_mv`](Self::read_vdda_mv), and undoes the internal divider of the VBAT input. On
+    /// the STM32F0 the divider is 2, so VBAT can be measured up to twice VDDA. The STM32F030,
+    /// STM32F070 and STM32L0 have no VBAT channel.
+    #[cfg(all(feature = "time", stm32f0, not(stm32f0x0)))]
+    pub async fn read_vbat_mv(&mut self) -> Result<u32, AdcError> {
+        let mut vbat = Vbat::acquire();
+        let sample = self.read_internal(&mut vbat).await;
+        self.disable_vbat(vbat);
+        let sample = sample? as u32;
+
+        let vdda_mv = self.read_vdda_mv().await?;
+        Ok(sample * vdda_mv * VBAT_DIVIDER / Self::max_count())
+    }
+
+    /// Measure VDDA, the temperature and, on the STM32F0, the battery voltage in one go.
+    ///
+    /// The internal reference and the temperature sensor are powered up together, so their
+    /// startup time is only waited for once, and VBAT is read after the temperature sensor. The two
+    /// share an input path on some parts, so each one is converted with the other one off, also if
+    /// it's enabled elsewhere, and switched back on afterwards. They are all converted against the
+    /// same VDDA measurement. Channels whose markers aren't held elsewhere are disabled again
+    /// afterwards, also if a read fails. Any resolution, alignment and oversampling can be used.
+    #[cfg(all(feature = "time", any(all(stm32f0, not(stm32f0x0)), stm32l0)))]
+    pub async fn read_internal_snapshot(&mut self) -> Result<InternalSnapshot, AdcError> {
+        let (mut vref, vref_was_off) = Vref::acquire();
+        let (mut temperature, temperature_was_off) = Temperature::acquire();
+
+        // Both take up to 10 µs to start up, see `enable_vref` and `enable_temperature`.
+        if vref_was_off || temperature_was_off {
+            Timer::after(Duration::from_micros(10)).await;
+        }
+
+        let vref_sample = self.read_internal(&mut vref).await;
+
+        // VBAT and the temperature sensor share an input path on some parts, so each one is
+        // converted with the other one off, even if a marker of it is held elsewhere. Its state is
+        // restored afterwards, also if the future is dropped.
+        #[cfg(stm32f0)]
+        let vbat_was_on = T::regs().ccr().read().vbaten();
+        #[cfg(stm32f0)]
+        let restore_vbat = OnDrop::new(|| T::regs().ccr().modify(|reg| reg.set_vbaten(vbat_was_on)));
+        #[cfg(stm32f0)]
+        T::regs().ccr().modify(|reg| reg.set_vbaten(false));
+
+        let temperature_sample = self.read_internal(&mut temperature).await;
+        #[cfg(stm32f0)]
+        drop(restore_vbat);
+        // The channels stay on for other holders of their markers.
+        self.disable_temperature(temperature);
+
+        #[cfg(stm32f0)]
+        let vbat_sample = {
+            let tsen_was_on = T::regs().ccr().read().tsen();
+            let restore_tsen = OnDrop::new(|| T::regs().ccr().modify(|reg| reg.set_tsen(tsen_was_on)));
+            T::regs().ccr().modify(|reg| reg.set_tsen(false));
+
+            let mut vbat = Vbat::acquire();
+            let sample = self.read_internal(&mut vbat).await;
+            self.disable_vbat(vbat);
+
+            drop(restore_tsen);
+            if tsen_was_on {
+                // Give the sensor its startup time again for whoever else reads it.
+                Timer::after(Duration::from_micros(10)).await;
+            }
+            sample
+        };
+
+        self.disable_vref(vref);
+
+        // The calibration values are 12-bit readings.
+        let to_12bit = |sample: u16| (sample as u32 * Resolution::TwelveBit.to_max_count() / Self::max_count()) as u16;
+        let vref_sample = to_12bit(vref_sample?);
+        let vdda_mv = self.vdda_mv(vref_sample);
+
+        Ok(InternalSnapshot {
+            vdda_mv,
+            temperature_c: self.temperature_to_celsius(to_12bit(temperature_sample?), vref_sample),
+            #[cfg(stm32f0)]
+            vbat_mv: vbat_sample? as u32 * vdda_mv * VBAT_DIVIDER / Self::max_count(),
+        })
+    }
+
+    /// Set the VDDA at which the factory `VREFINT_CAL` value was measured, in millivolts.
+    ///
+    /// This is not the VDDA of the board, which [`vdda_mv`](Self::vdda_mv) measures, but a
+    /// property of the factory calibration: 3.3 V on the STM32F0 and 3.0 V on the STM32L0, see
+    /// [`VDDA_CALIB_MV`]. Boards running VDDA at another voltage don't need to change this. Only
+    /// change it for parts whose datasheet specifies a different calibration voltage.
+    pub fn set_vdda_calib(&mut self, mv: u32) {
+        self.vdda_calib_mv = mv;
+    }
+
+    /// Convert a sample to millivolts, given the VDDA it was measured against.
+    ///
+    /// The sample is scaled by the currently configured [`Resolution`] and oversampling.
+    pub fn sample_to_mv(&self, sample: u16, vdda_mv: u32) -> u16 {
+        (sample as u32 * vdda_mv / Self::max_count()) as u16
+    }
+
+    /// Convert a sample to millivolts using a [`Vref`] sample and the typical internal reference
+    /// voltage.
+    ///
+    /// This is the free [`sample_to_mv`](crate::adc::sample_to_mv) at the current [`Resolution`], so
+    /// both samples must be right-aligned and not oversampled.
+    pub fn sample_to_mv_typical(&self, sample: u16, vref_int_sample: u16) -> u16 {
+        sample_to_mv(sample, vref_int_sample, Self::resolution())
+    }
+
+    /// Convert a sample to millivolts using a [`Vref`] sample and the factory calibration, with
+    /// integer math only.
+    ///
+    /// This is [`vdda_mv`](Self::vdda_mv) and [`sample_to_mv`](Self::sample_to_mv) in one step,
+    /// for parts without an FPU. The intermediate products are 64-bit, so they don't overflow even
+    /// for oversampled 16-bit samples, and the result is rounded rather than truncated. Both
+    /// samples can be taken at any resolution, alignment and oversampling, as long as it's the same
+    /// for both.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `vrefint_sample` is zero.
+    #[cfg(any(stm32f0, stm32l0))]
+    pub fn sample_to_mv_calibrated(&self, sample: u16, vrefint_sample: u16) -> u32 {
+        let vrefint_cal = calibration::read(calibration::VREFINT_CAL);
+        calibrated_mv(sample, vrefint_sample, vrefint_cal, self.vdda_calib_mv)
+    }
+
+    /// Full-scale count of `dr` with the current configuration.
+    fn max_count() -> u32 {
+        let max_count = full_scale_count(Self::resolution(), Self::align());
+
+        #[cfg(stm32l0)]
+        {
+            let cfgr2 = T::regs().cfgr2().read();
+            if cfgr2.ovse() {
+                return (max_count << (cfgr2.ovsr() as u32 + 1)) >> cfgr2.ovss() as u32;
+            }
+        }
+
+        max_count
+    }
+
+    /// Enable the hardware oversampler.
+    ///
+    /// Each sample in `dr` then holds the sum of `ratio` conversions, shifted right by `shift`.
+    /// Pick a shift that keeps the result within 16 bits, e.g. [`OversamplingShift::Shift4`] for
+    /// [`OversamplingRatio::X16`] returns the 12-bit mean.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a conversion is running, like [`set_resolution`](Self::set_resolution).
+    #[cfg(stm32l0)]
+    pub fn set_oversampling(&mut self, ratio: OversamplingRatio, shift: OversamplingShift) {
+        self.abort_pending();
+        Self::assert_not_converting();
+        T::regs().cfgr2().modify(|reg| {
+            reg.set_ovsr(ratio as u8);
+            reg.set_ovss(shift as u8);
+            reg.set_ovse(true);
+        });
+    }
+
+    /// Disable the hardware oversampler.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a conversion is running, like [`set_resolution`](Self::set_resolution).
+    #[cfg(stm32l0)]
+    pub fn disable_oversampling(&mut self) {
+        self.abort_pending();
+        Self::assert_not_converting();
+        T::regs().cfgr2().modify(|reg| reg.set_ovse(false));
+    }
+
+    fn resolution() -> Resolution {
+        T::regs().cfgr1().read().res().into()
+    }
+
+    /// Set the sample time of all channels.
+    ///
+    /// The default is the shortest one, [`SampleTime::Cycles1_5`], which is only long enough for
+    /// low-impedance sources like an op-amp output. Sources with a higher impedance, e.g. a
+    /// resistive divider, read low unless the sample time is longer: the datasheet gives the
+    /// maximum source impedance per sample time, and [`SampleTime::from_min_cycles`] or
+    /// [`sample_time_for_us`](Self::sample_time_for_us) pick one. Reads of the internal channels
+    /// use at least the sample time the datasheet requires for them, whatever is set here.
+    pub fn set_sample_time(&mut self, sample_time: SampleTime) {
+        self.sample_time = sample_time;
+        // Every conversion programs SMPR anyway, this is for `current_sample_time`. SMPR can't be
+        // written during a conversion, which then picks up the new sample time next time.
+        if !T::regs().cr().read().adstart() {
+            T::regs().smpr().modify(|reg| reg.set_smp(sample_time.into()));
+        }
+    }
+
+    /// The sample time programmed in `SMPR`, read back from the register.
+    ///
+    /// This is the one set with [`set_sample_time`](Self::set_sample_time), unless the last
+    /// conversion used another one: a per-channel sample time, or the longer one of an internal
+    /// channel.
+    pub fn current_sample_time(&self) -> SampleTime {
+        T::regs().smpr().read().smp().into()
+    }
+
+    /// The resolution programmed in `CFGR1`, read back from the register.
+    pub fn current_resolution(&self) -> Resolution {
+        Self::resolution()
+    }
+
+    /// Set the sample time of a single channel, overriding the one set with
+    /// [`set_sample_time`](Self::set_sample_time).
+    ///
+    /// The STM32F0 ADC only has one SMP register shared by all channels, so the driver
+    /// reprograms it before every conversion.
+    pub fn set_channel_sample_time(&mut self, channel: u8, sample_time: SampleTime) {
+        debug_check_channel(channel);
+        self.channel_sample_times[channel as usize] = Some(sample_time);
+    }
+
+    /// The sample time to convert `channel` with.
+    ///
+    /// Every read looks this up, so it's also where channel numbers get checked.
+    fn channel_sample_time(&self, channel: u8) -> SampleTime {
+        debug_check_channel(channel);
+        self.channel_sample_times[channel as usize].unwrap_or(self.sample_time)
+    }
+
+    /// Select the clock the ADC runs from.
+    ///
+    /// `CKMODE` can only be changed while the ADC is disabled, so the ADC is briefly disabled and
+    /// then waited on until it's ready again, unless it was disabled with
+    /// [`disable`](Self::disable) before. The calibration is kept. Sample times picked with
+    /// [`sample_time_for_us`](Self::sample_time_for_us) depend on the clock, so pick them again
+    /// after changing it.
+    ///
+    /// Fails with [`AdcError::EnableFailed`] if the ADC doesn't become ready on the new clock, e.g.
+    /// because it isn't running. The new clock source is kept selected in that case.
+    pub fn set_clock_source(&mut self, source: AdcClockSource) -> Result<(), AdcError> {
+        self.abort_pending();
+        // Leave the ADC disabled if it was, e.g. after `disable`.
+        let was_enabled = T::regs().cr().read().aden();
+        disable_adc(T::regs());
+        T::regs().cfgr2().modify(|reg| reg.set_ckmode(source.into()));
+        if was_enabled {
+            try_enable_adc(T::regs())?;
+        }
+
+        Ok(())
+    }
+
+    fn frequency() -> Hertz {
+        let pclk = unsafe { crate::rcc::get_freqs() }.apb2;
+
+        match T::regs().cfgr2().read().ckmode() {
+            Ckmode::PCLK_DIV2 => pclk / 2u32,
+            Ckmode::PCLK_DIV4 => pclk / 4u32,
+            _ => ASYNC_CLOCK_FREQ,
+        }
+    }
+
+    /// The ADC clock frequency the driver assumes, in hertz.
+    ///
+    /// With the asynchronous clock this is the nominal frequency of the HSI14 (HSI16 on the
+    /// STM32L0) oscillator.
+    pub fn clock_hz(&self) -> u32 {
+        Self::frequency().0
+    }
+
+    /// Estimate how long a conversion with the current sample time and resolution takes, in
+    /// microseconds, rounded up.
+    ///
+    /// It's the sample time plus the successive approximation time, which is the resolution in bits
+    /// plus half a cycle. Per-channel sample times and the startup time in auto-off mode aren't
+    /// included.
+    pub fn conversion_time_us(&self) -> u32 {
+        let sample_half_cycles = self.sample_time.half_cycles();
+        let conversion_half_cycles = 2 * Self::resolution().bits() as u32 + 1;
+
+        let half_cycles = (sample_half_cycles + conversion_half_cycles) as u64;
+        let half_cycles_per_s = 2 * Self::frequency().0 as u64;
+        ((half_cycles * 1_000_000 + half_cycles_per_s - 1) / half_cycles_per_s) as u32
+    }
+
+    /// The shortest sample time lasting at least `us` microseconds at the current ADC clock.
+    ///
+    /// The internal channels need this: Vbat and the temperature sensor must be sampled for at
+    /// least 4 µs, which is 56 cycles of HSI14 but 96 cycles of PCLK/2 at 48 MHz.
+    pub fn sample_time_for_us(&self, us: u32) -> SampleTime {
+        // Round up, a sample time a fraction of a cycle short of `us` is still too short.
+        let cycles = (us as u64 * Self::frequency().0 as u64 + 1_000_000 - 1) / 1_000_000;
+        SampleTime::from_min_cycles(cycles.min(u16::MAX as u64) as u16)
+    }
+
+    /// Power the ADC down automatically between conversions.
+    ///
+    /// The ADC then only draws current while converting, see the ADC consumption figures in the
+    /// device datasheet's "12-bit ADC characteristics" table. The price is the ADC startup time
+    /// (`tSTAB` in the same table), which the hardware inserts before every conversion and adds to
+    /// the latency of each read.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a conversion is running, like [`set_resolution`](Self::set_resolution).
+    pub fn set_auto_off(&mut self, enabled: bool) {
+        self.abort_pending();
+        Self::assert_not_converting();
+        T::regs().cfgr1().modify(|reg| reg.set_autoff(enabled));
+    }
+
+    /// Make the ADC wait for `dr` to be read before starting the next conversion.
+    ///
+    /// This is the `WAIT` bit, also called auto-delay mode. It prevents overruns in continuous
+    /// mode, e.g. with [`into_continuous`](Self::into_continuous), at the cost of the sample rate:
+    /// it's then limited by how fast the samples are consumed rather than by the ADC clock and
+    /// sample time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a conversion is running, like [`set_resolution`](Self::set_resolution).
+    pub fn set_auto_delay(&mut self, enabled: bool) {
+        self.abort_pending();
+        Self::assert_not_converting();
+        T::regs().cfgr1().modify(|reg| reg.set_wait(enabled));
+    }
+
+    /// Set the alignment of the samples within the 16-bit result.
+    ///
+    /// Left-aligned samples can be used directly as e.g. a PWM duty cycle. The millivolt
+    /// helpers take the alignment into account.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a conversion is running, like [`set_resolution`](Self::set_resolution).
+    pub fn set_align(&mut self, align: DataAlign) {
+        self.abort_pending();
+        Self::assert_not_converting();
+        T::regs().cfgr1().modify(|reg| reg.set_align(align.into()));
+    }
+
+    fn align() -> DataAlign {
+        match T::regs().cfgr1().read().align() {
+            Align::RIGHT => DataAlign::Right,
+            Align::LEFT => DataAlign::Left,
+        }
+    }
+
+    /// Set the resolution of the samples.
+    ///
+    /// The ADC has to be disabled to change the resolution. Fails with
+    /// [`AdcError::EnableFailed`] if it doesn't become ready again afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a conversion is running, e.g. after
+    /// [`start_hardware_triggered`](Self::start_hardware_triggered): changing the resolution in
+    /// the middle of it would corrupt the sample. Stop the conversions first.
+    pub fn set_resolution(&mut self, resolution: Resolution) -> Result<(), AdcError> {
+        // A conversion left by `try_read_channel` is the driver's own, it can just be dropped.
+        self.abort_pending();
+        Self::assert_not_converting();
+
+        // RES can only be written while the ADC is disabled. Leave it disabled if it was, e.g.
+        // after `disable`.
+        let was_enabled = T::regs().cr().read().aden();
+        disable_adc(T::regs());
+        T::regs().cfgr1().modify(|reg| reg.set_res(resolution.into()));
+        if was_enabled {
+            try_enable_adc(T::regs())?;
+        }
+
+        Ok(())
+    }
+
+    /// `cfgr1` and `cfgr2` must not be written while a conversion is running. The setters writing
+    /// them use this rather than silently aborting the conversion.
+    fn assert_not_converting() {
+        assert!(
+            !T::regs().cr().read().adstart(),
+            "ADC configuration can't be changed while a conversion is running"
+        );
+    }
+
+    /// Correct the samples returned by [`read`](Self::read) for the offset and gain error of the
+    /// analog front-end.
+    ///
+    /// Samples are corrected as `(raw - offset) * gain_num / gain_den`, saturating to the range of
+    /// a `u16`. [`read`](Self::read), [`read_timeout`](Self::read_timeout),
+    /// [`read_preconfigured`](Self::read_preconfigured), [`blocking_read`](Self::blocking_read)
+    /// and [`blocking_read_timeout`](Self::blocking_read_timeout) apply the correction, all other
+    /// reads, and [`read_raw`](Self::read_raw), return raw samples.
+    pub fn set_correction(&mut self, offset: i16, gain_num: u16, gain_den: u16) {
+        assert!(gain_den != 0, "correction gain denominator must not be zero");
+        self.correction = (offset, gain_num, gain_den);
+    }
+
+    /// Stop correcting samples.
+    pub fn clear_correction(&mut self) {
+        self.correction = NO_CORRECTION;
+    }
+
+    fn correct(&self, raw: u16) -> u16 {
+        let (offset, gain_num, gain_den) = self.correction;
+        let corrected = (raw as i64 - offset as i64) * gain_num as i64 / gain_den as i64;
+        corrected.clamp(0, u16::MAX as i64) as u16
+    }
+
+    /// Read a pin, applying the correction set with [`set_correction`](Self::set_correction).
+    ///
+    /// This is cancel-safe: if the future is dropped before the conversion completes, e.g. because
+    /// it lost a `select` or timed out, the conversion is stopped. All other single-conversion reads
+    /// behave the same.
+    ///
+    /// # Power
+    ///
+    /// Waiting for the conversion doesn't keep the core busy. The task is woken by the
+    /// end-of-conversion interrupt, so a thread-mode executor with nothing else to run goes to sleep
+    /// with `WFE` until the interrupt fires: the constructors enable the ADC interrupt in the NVIC,
+    /// and an enabled interrupt wakes the core from `WFE` like from `WFI`, without `SEVONPEND`.
+    /// The ADC keeps converting in Sleep mode, but not in Stop mode. The `low-power` executor
+    /// doesn't enter Stop mode while an [`Adc`] exists, since the driver holds the ADC clock
+    /// enabled, so it sleeps the same way the plain executor does.
+    pub async fn read<P>(&mut self, pin: &mut P) -> Result<u16, AdcError>
+    where
+        P: AdcPin<T>,
+    {
+        let raw = self.read_raw(pin).await?;
+        Ok(self.correct(raw))
+    }
+
+    /// Read a pin that's already in analog mode, like [`read`](Self::read).
+    ///
+    /// [`read`](Self::read) puts the pin into analog mode every time, which costs a GPIO register
+    /// write. The [`Analog`](crate::gpio::Analog) driver does that once, when it's created.
+    pub async fn read_preconfigured<P>(&mut self, pin: &crate::gpio::Analog<'_, P>) -> Result<u16, AdcError>
+    where
+        P: AdcPin<T> + crate::gpio::Pin,
+    {
+        let raw = self.read_channel(pin.pin.pin.channel()).await?;
+        Ok(self.correct(raw))
+    }
+
+    /// Read a pin like [`read`](Self::read), returning the sample along with its resolution.
+    pub async fn read_sample<P>(&mut self, pin: &mut P) -> Result<Sample, AdcError>
+    where
+        P: AdcPin<T>,
+    {
+        let value = self.read(pin).await?;
+
+        Ok(Sample {
+            value,
+            resolution: Self::resolution(),
+            full_scale: Self::max_count(),
+        })
+    }
+
+    /// Read a pin, without the correction set with [`set_correction`](Self::set_correction).
+    pub async fn read_raw<P>(&mut self, pin: &mut P) -> Result<u16, AdcError>
+    where
+        P: AdcPin<T>,
+    {
+        let channel = pin.channel();
+        pin.configure_analog();
+        self.read_channel(channel).await
+    }
+
+    /// Read several pins, one after the other.
+    ///
+    /// The samples are returned in the order of `pins`.
+    pub async fn read_many<const N: usize>(&mut self, pins: [&mut dyn AdcPin<T>; N]) -> Result<[u16; N], AdcError> {
+        let mut samples = [0; N];
+
+        for (pin, sample) in pins.into_iter().zip(samples.iter_mut()) {
+            pin.configure_analog();
+            *sample = self.read_channel(pin.channel()).await?;
+        }
+
+        Ok(samples)
+    }
+
+    /// Fill `out` with samples of `pins`, until it's full or `cancel` completes.
+    ///
+    /// The pins are read round-robin, one conversion at a time, so this works without DMA and only
+    /// goes as fast as the task gets to run. Returns the number of samples written. Dropping the
+    /// future stops the conversion in flight.
+    pub async fn stream_into<const N: usize>(
+        &mut self,
+        pins: [&mut dyn AdcPin<T>; N],
+        out: &mut [u16],
+        cancel: impl Future,
+    ) -> Result<usize, AdcError> {
+        assert!(N > 0, "no pins to read");
+
+        let channels = pins.map(|pin| {
+            pin.configure_analog();
+            pin.channel()
+        });
+        let mut cancel = pin!(cancel);
+
+        for (i, sample) in out.iter_mut().enumerate() {
+            match select(self.read_channel(channels[i % N]), cancel.as_mut()).await {
+                Either::First(result) => *sample = result?,
+                Either::Second(_) => return Ok(i),
+            }
+        }
+
+        Ok(out.len())
+    }
+
+    /// Capture a burst of samples of `pin` into `out`, as fast as the ADC converts.
+    ///
+    /// The ADC converts continuously and the interrupt handler moves every sample into `out`, only
+    /// waking the task once it's full, so no DMA channel is needed. The handler has to keep up with
+    /// the conversions: conversions faster than it, or higher priority interrupts delaying it, make
+    /// the capture fail with [`AdcError::Overrun`] instead of silently skipping samples.
+    ///
+    /// The achievable rate depends on the core and ADC clocks and on the interrupt load, and hasn't
+    /// been characterized yet. The `adc_burst` example of the STM32F0 measures it on a given board.
+    ///
+    /// Dropping the future stops the conversions.
+    pub async fn capture_burst<P>(&mut self, pin: &mut P, out: &mut [u16]) -> Result<(), AdcError>
+    where
+        P: AdcPin<T>,
+    {
+        Self::check_enabled()?;
+        Self::check_not_discontinuous();
+        self.abort_pending();
+        if out.is_empty() {
+            return Ok(());
+        }
+
+        let channel = pin.channel();
+        pin.configure_analog();
+
+        let len = out.len();
+        let state = T::state();
+        state.burst_buf.store(out.as_mut_ptr(), Ordering::Relaxed);
+        state.burst_pos.store(0, Ordering::Relaxed);
+        state.burst_len.store(len, Ordering::Release);
+
+        // Stop the handler from writing to `out` before it goes away.
+        let _on_drop = OnDrop::new(|| {
+            T::regs().ier().modify(|w| {
+                w.set_eocie(false);
+                w.set_ovrie(false);
+            });
+            stop_conversion(T::regs());
+            T::regs().cfgr1().modify(|reg| reg.set_cont(false));
+            T::state().burst_len.store(0, Ordering::Relaxed);
+        });
+
+        T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+        T::regs()
+            .smpr()
+            .modify(|reg| reg.set_smp(self.channel_sample_time(channel).into()));
+        T::regs().isr().modify(|reg| {
+            reg.set_eoc(true);
+            reg.set_eosmp(true);
+            reg.set_ovr(true);
+        });
+        T::regs().cfgr1().modify(|reg| reg.set_cont(true));
+        T::regs().ier().modify(|w| {
+            w.set_eocie(true);
+            w.set_ovrie(true);
+        });
+        T::regs().cr().modify(|reg| reg.set_adstart(true));
+
+        poll_fn(|cx| {
+            state.waker.register(cx.waker());
+
+            if T::regs().isr().read().ovr() {
+                Poll::Ready(Err(AdcError::Overrun))
+            } else if state.burst_pos.load(Ordering::Acquire) >= len {
+                Poll::Ready(Ok(()))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Fill `out` with samples of `pin`, taken at `rate_hz` samples per second.
+    ///
+    /// The conversions are paced with a [`Ticker`], so the jitter is that of the task getting to
+    /// run, and the rate is limited by the `embassy-time` tick rate. Fails with
+    /// [`AdcError::SampleRateTooHigh`] if a conversion isn't complete by the time the next one is
+    /// due, as the rate then can't be kept up. For jitter-free sampling, use a hardware trigger
+    /// with [`start_hardware_triggered`](Self::start_hardware_triggered) instead.
+    #[cfg(feature = "time")]
+    pub async fn sample_at<P>(&mut self, pin: &mut P, rate_hz: u32, out: &mut [u16]) -> Result<(), AdcError>
+    where
+        P: AdcPin<T>,
+    {
+        assert!(rate_hz > 0);
+
+        let channel = pin.channel();
+        pin.configure_analog();
+
+        let period = Duration::from_hz(rate_hz as u64);
+        let mut due = Instant::now();
+        let mut ticker = Ticker::every(period);
+
+        for sample in out.iter_mut() {
+            ticker.next().await;
+            due += period;
+
+            *sample = self.read_channel(channel).await?;
+
+            if Instant::now() >= due + period {
+                return Err(AdcError::SampleRateTooHigh);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read a pin `n` times back to back and return the rounded mean of the samples.
+    pub async fn read_averaged<P>(&mut self, pin: &mut P, n: u32) -> Result<u16, AdcError>
+    where
+        P: AdcPin<T>,
+    {
+        assert!(n > 0);
+
+        let channel = pin.channel();
+        pin.configure_analog();
+
+        self.abort_pending();
+        T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+        let sample_time = self.channel_sample_time(channel);
+
+        let mut sum: u64 = 0;
+        for _ in 0..n {
+            sum += self.convert(sample_time).await? as u64;
+        }
+
+        Ok(rounded_mean(sum, n))
+    }
+
+    /// Read a pin, giving up if the conversion doesn't complete within `timeout`.
+    ///
+    /// On timeout the conversion is aborted, so the `Adc` can be used again right away. If the ADC
+    /// doesn't acknowledge the abort either, e.g. because its clock stopped, waiting for it is
+    /// bounded and the ADC is disabled instead, see [`enable`](Self::enable).
+    #[cfg(feature = "time")]
+    pub async fn read_timeout<P>(&mut self, pin: &mut P, timeout: Duration) -> Result<u16, AdcError>
+    where
+        P: AdcPin<T>,
+    {
+        // Dropping the read on timeout aborts the conversion.
+        match embassy_time::with_timeout(timeout, self.read(pin)).await {
+            Ok(result) => result,
+            Err(_) => Err(AdcError::Timeout),
+        }
+    }
+
+    /// Read a pin, giving up when `cancel` is signaled, e.g. by a fault handler in another task.
+    ///
+    /// On cancellation the conversion is stopped and its interrupt and flags cleared, so the `Adc`
+    /// is in the same state as after a completed read and can be used again right away. A signal
+    /// that was already raised cancels the read before it starts, and the signal is reset by the
+    /// cancellation.
+    pub async fn read_cancellable<P, M>(&mut self, pin: &mut P, cancel: &Signal<M, ()>) -> Result<u16, AdcError>
+    where
+        P: AdcPin<T>,
+        M: RawMutex,
+    {
+        // `select` polls the signal first, and dropping the read aborts the conversion.
+        match select(cancel.wait(), self.read(pin)).await {
+            Either::First(()) => Err(AdcError::Cancelled),
+            Either::Second(result) => result,
+        }
+    }
+
+    /// Read a pin, ignoring overruns.
+    ///
+    /// On overrun this returns whatever `dr` holds, which may belong to a later conversion.
+    #[deprecated(note = "use `read` and handle `AdcError::Overrun` instead")]
+    pub async fn read_unchecked<P>(&mut self, pin: &mut P) -> u16
+    where
+        P: AdcPin<T>,
+    {
+        match self.read(pin).await {
+            Ok(sample) => sample,
+            Err(_) => T::regs().dr().read().data(),
+        }
+    }
+
+    /// Read an internal channel.
+    ///
+    /// The channel is sampled for at least as long as the datasheet requires, even if a shorter
+    /// sample time is configured. The configured sample time is left as it is.
+    pub async fn read_internal(&mut self, channel: &mut impl InternalChannel<T>) -> Result<u16, AdcError> {
+        let channel = channel.channel();
+        self.abort_pending();
+
+        T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+        self.convert(self.internal_sample_time(channel)).await
+    }
+
+    /// The sample time for an internal channel: the configured one, clamped to the datasheet minimum.
+    fn internal_sample_time(&self, channel: u8) -> SampleTime {
+        let sample_time = self.channel_sample_time(channel);
+
+        match INTERNAL_MIN_SAMPLE_TIMES_US.iter().find(|(c, _)| *c == channel) {
+            Some(&(_, us)) => sample_time.max(self.sample_time_for_us(us)),
+            None => sample_time,
+        }
+    }
+
+    /// Read several internal channels, one after the other.
+    ///
+    /// Like [`read_internal`](Self::read_internal), each channel is sampled for at least as long as
+    /// the datasheet requires. The samples are returned in the order of `channels`.
+    ///
+    /// On the STM32F0, VBAT and the temperature sensor share the same input path on some parts, so
+    /// asking for both fails with [`AdcError::ConflictingChannels`]. The STM32L0 has no VBAT input.
+    pub async fn read_internal_sequence<const N: usize>(
+        &mut self,
+        channels: [&mut dyn InternalChannel<T>; N],
+    ) -> Result<[u16; N], AdcError> {
+        #[cfg(not(stm32l0))]
+        {
+            let has = |wanted: u8| channels.iter().any(|channel| channel.channel() == wanted);
+            if has(VBAT_CHANNEL) && has(TEMPERATURE_CHANNEL) {
+                return Err(AdcError::ConflictingChannels);
+            }
+        }
+
+        self.abort_pending();
+        let mut samples = [0; N];
+
+        for (channel, sample) in channels.into_iter().zip(samples.iter_mut()) {
+            let channel = channel.channel();
+
+            T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+            *sample = self.convert(self.internal_sample_time(channel)).await?;
+        }
+
+        Ok(samples)
+    }
+
+    /// Start conversions from a hardware trigger instead of software.
+    ///
+    /// Conversions started afterwards with `read`,
+    /// [`read_sequence`](Self::read_sequence) or [`into_continuous`](Self::into_continuous) set
+    /// `adstart` as usual, after which the ADC waits for `trigger` instead of starting right away.
+    /// Single reads stop the ADC again once their sample is in, so every read waits for a trigger
+    /// of its own and the next one can select another channel.
+    /// Together with [`read_sequence`](Self::read_sequence) this gives jitter-free periodic
+    /// sampling of several channels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a conversion is running, e.g. one armed by an earlier call: stop it with
+    /// [`stop_hardware_triggered`](Self::stop_hardware_triggered) first.
+    #[cfg(any(stm32f0, stm32l0))]
+    pub fn start_hardware_triggered(&mut self, trigger: ExternalTrigger, edge: TriggerEdge) {
+        assert!(edge != TriggerEdge::None, "hardware triggering needs a trigger edge");
+
+        self.abort_pending();
+        Self::assert_not_converting();
+        T::regs().cfgr1().modify(|reg| {
+            reg.set_extsel(trigger as u8);
+            reg.set_exten(edge.into());
+        });
+    }
+
+    /// Read `pin` once `trigger` fires on `edge`, e.g. when an external sensor signals that its
+    /// output is ready.
+    ///
+    /// The ADC is armed right away and the sample is awaited through the interrupt as usual. See
+    /// [`ExternalTrigger`] for the sources: only the STM32L0 can use a GPIO directly. Software
+    /// triggering is restored afterwards, also if the future is dropped before the trigger fired.
+    #[cfg(any(stm32f0, stm32l0))]
+    pub async fn read_on_event<P>(
+        &mut self,
+        pin: &mut P,
+        trigger: ExternalTrigger,
+        edge: TriggerEdge,
+    ) -> Result<u16, AdcError>
+    where
+        P: AdcPin<T>,
+    {
+        let channel = pin.channel();
+        pin.configure_analog();
+        self.abort_pending();
+
+        self.start_hardware_triggered(trigger, edge);
+        let _on_drop = OnDrop::new(|| {
+            stop_conversion(T::regs());
+            T::regs().cfgr1().modify(|reg| reg.set_exten(TriggerEdge::None.into()));
+        });
+
+        T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+        self.convert(self.channel_sample_time(channel)).await
+    }
+
+    /// Go back to starting conversions from software.
+    pub fn stop_hardware_triggered(&mut self) {
+        stop_conversion(T::regs());
+        T::regs().cfgr1().modify(|reg| reg.set_exten(TriggerEdge::None.into()));
+    }
+
+    /// Convert the channels of a sequence in groups of `count`, one group per trigger.
+    ///
+    /// The STM32F0 ADC has no subgroup length, so it can only convert a single channel per trigger
+    /// and `count` must be 1. Discontinuous and continuous mode can't be used together, so the
+    /// continuous methods like [`into_continuous`](Self::into_continuous) panic while this is
+    /// enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a conversion is running, like [`set_resolution`](Self::set_resolution).
+    pub fn set_discontinuous(&mut self, count: u8) {
+        assert!(
+            (1..=MAX_DISCONTINUOUS_COUNT).contains(&count),
+            "discontinuous group length must be between 1 and {}",
+            MAX_DISCONTINUOUS_COUNT
+        );
+
+        self.abort_pending();
+        Self::assert_not_converting();
+        T::regs().cfgr1().modify(|reg| reg.set_discen(true));
+    }
+
+    /// Convert the whole sequence on every trigger again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a conversion is running, like [`set_resolution`](Self::set_resolution).
+    pub fn disable_discontinuous(&mut self) {
+        self.abort_pending();
+        Self::assert_not_converting();
+        T::regs().cfgr1().modify(|reg| reg.set_discen(false));
+    }
+
+    fn check_not_discontinuous() {
+        assert!(
+            !T::regs().cfgr1().read().discen(),
+            "continuous conversions can't be used in discontinuous mode"
+        );
+    }
+
+    /// Wait until `pin` leaves the `low..=high` window, returning the sample that did.
+    ///
+    /// The ADC converts `pin` continuously while waiting, and the CPU is only interrupted once the
+    /// analog watchdog fires. The thresholds are right-aligned samples at the current resolution,
+    /// like the returned sample. The watchdog itself always compares 12-bit values, so they're
+    /// scaled up for it. Thresholds above the largest sample at the current resolution are clamped
+    /// to it.
+    ///
+    /// Dropping the future, e.g. when `embassy_time::with_timeout` expires, stops the conversions
+    /// and disables the watchdog.
+    pub async fn watch<P>(&mut self, pin: &mut P, low: u16, high: u16) -> u16
+    where
+        P: AdcPin<T>,
+    {
+        Self::check_enabled().expect("ADC is disabled");
+        Self::check_not_discontinuous();
+        self.abort_pending();
+
+        let channel = pin.channel();
+        pin.configure_analog();
+
+        stop_conversion(T::regs());
+        T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+        T::regs()
+            .smpr()
+            .modify(|reg| reg.set_smp(self.channel_sample_time(channel).into()));
+        let resolution = Self::resolution();
+        T::regs().tr().write(|reg| {
+            reg.set_lt(watchdog_threshold(low, resolution));
+            reg.set_ht(watchdog_threshold(high, resolution));
+        });
+        T::regs().cfgr1().modify(|reg| {
+            reg.set_awdch(channel);
+            reg.set_awdsgl(true);
+            reg.set_awden(true);
+            reg.set_cont(true);
+            // Nobody reads `dr` while waiting, so let new samples overwrite it.
+            reg.set_ovrmod(true);
+        });
+
+        let _on_drop = OnDrop::new(|| {
+            stop_conversion(T::regs());
+            T::regs().ier().modify(|w| w.set_awdie(false));
+            T::regs().cfgr1().modify(|reg| {
+                reg.set_awden(false);
+                reg.set_cont(false);
+                reg.set_ovrmod(false);
+            });
+        });
+
+        T::regs().isr().modify(|reg| {
+            reg.set_awd(true);
+            reg.set_eoc(true);
+            reg.set_ovr(true);
+        });
+        T::regs().ier().modify(|w| w.set_awdie(true));
+        T::regs().cr().modify(|reg| reg.set_adstart(true));
+
+        poll_fn(|cx| {
+            T::state().waker.register(cx.waker());
+
+            if T::regs().ier().read().awdie() {
+                Poll::Pending
+            } else {
+                Poll::Ready(())
+            }
+        })
+        .await;
+
+        T::regs().dr().read().data()
+    }
+
+    /// Start converting `pin` continuously.
+    ///
+    /// The ADC is started once with `CONT` set and the returned [`ContinuousAdc`] yields every
+    /// sample as it is taken. The interrupt handler moves each result out of `dr`, so a sample that
+    /// isn't consumed before the next one completes is dropped in favour of the newer one.
+    ///
+    /// Each sample costs one interrupt plus one wake and poll of the consuming task. If the
+    /// interrupt handler can't take a result out of `dr` before the next conversion ends, the
+    /// stream yields [`AdcError::Overrun`] and carries on with the following samples. Use a longer
+    /// [`SampleTime`] to slow the ADC down to a rate the system can keep up with.
+    ///
+    /// Dropping the stream stops the conversions so the `Adc` can be used again.
+    pub fn into_continuous<P>(&mut self, pin: &mut P) -> ContinuousAdc<'_, 'd, T>
+    where
+        P: AdcPin<T>,
+    {
+        Self::check_enabled().expect("ADC is disabled");
+        Self::check_not_discontinuous();
+        self.abort_pending();
+
+        let channel = pin.channel();
+        pin.configure_analog();
+
+        let state = T::state();
+        state.sample_ready.store(false, Ordering::Relaxed);
+        state.overrun.store(false, Ordering::Relaxed);
+        state.continuous.store(true, Ordering::Release);
+
+        T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+        T::regs()
+            .smpr()
+            .modify(|reg| reg.set_smp(self.channel_sample_time(channel).into()));
+        T::regs().cfgr1().modify(|reg| reg.set_cont(true));
+
+        T::regs().isr().modify(|reg| {
+            reg.set_eoc(true);
+            reg.set_eosmp(true);
+            reg.set_ovr(true);
+        });
+        T::regs().ier().modify(|w| {
+            w.set_eocie(true);
+            w.set_ovrie(true);
+        });
+        T::regs().cr().modify(|reg| reg.set_adstart(true));
+
+        ContinuousAdc { adc: self }
+    }
+
+    /// Convert `pin` `count` times, yielding each sample as soon as it's taken.
+    ///
+    /// Unlike [`into_continuous`](Self::into_continuous), the next conversion is only started
+    /// when the stream is polled for it, so a consumer slower than the ADC never loses a sample to
+    /// an overrun: the ADC waits for it instead. The stream ends after `count` samples, and
+    /// dropping it early stops the conversion in progress, so the `Adc` can be used again either
+    /// way. Samples aren't corrected.
+    pub fn burst_stream<P>(&mut self, pin: &mut P, count: usize) -> BurstStream<'_, 'd, T>
+    where
+        P: AdcPin<T>,
+    {
+        Self::check_enabled().expect("ADC is disabled");
+        Self::check_not_discontinuous();
+        self.abort_pending();
+
+        let channel = pin.channel();
+        pin.configure_analog();
+
+        T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+        T::regs()
+            .smpr()
+            .modify(|reg| reg.set_smp(self.channel_sample_time(channel).into()));
+
+        BurstStream {
+            _adc: self,
+            remaining: count,
+            converting: false,
+        }
+    }
+
+    /// Convert several channels in a single scan, using DMA to move the results into `out`.
+    ///
+    /// The ADC always scans the selected channels in ascending order, so `out[i]` holds the
+    /// result of the i-th channel of [`AdcSequence::channels`], the i-th lowest one.
+    ///
+    /// All channels of a scan share the single SMP register, so the longest sample time of the
+    /// channels involved is used for the whole scan.
+    ///
+    /// The ADC interrupts stay disabled during the scan, so the samples don't interrupt the CPU and
+    /// the task is only woken by the DMA transfer-complete interrupt.
+    ///
+    /// If the DMA stops before `out` is filled, including on a transfer error like `out` being in
+    /// memory the DMA can't access, this fails with [`AdcError::Dma`] instead of returning a
+    /// partially filled buffer. Dropping the future stops the scan.
+    ///
+    /// # Panics
+    ///
+    /// Panics in discontinuous mode, which would only convert part of the sequence.
+    pub async fn read_sequence(
+        &mut self,
+        dma: &mut impl RxDma<T>,
+        sequence: &AdcSequence,
+        out: &mut [u16],
+    ) -> Result<(), AdcError> {
+        if sequence.len() != out.len() {
+            return Err(AdcError::LengthMismatch);
+        }
+        Self::check_enabled()?;
+        Self::check_not_discontinuous();
+        if out.is_empty() {
+            return Ok(());
+        }
+        self.abort_pending();
+
+        let sample_time = sequence
+            .channels()
+            .map(|channel| self.channel_sample_time(channel))
+            .max()
+            .unwrap();
+
+        T::regs().chselr().write(|reg| {
+            for channel in sequence.channels() {
+                reg.set_chselx(channel as usize, true);
+            }
+        });
+        T::regs().smpr().modify(|reg| reg.set_smp(sample_time.into()));
+        T::regs().isr().modify(|reg| {
+            reg.set_eoc(true);
+            reg.set_eosmp(true);
+            reg.set_ovr(true);
+        });
+        // Only the DMA wakes the task, the ADC interrupt would just add spurious wakeups.
+        T::regs().ier().write(|_| {});
+
+        // `new()` leaves DMA disabled for the calibration, so turn it back on for the scan only.
+        T::regs().cfgr1().modify(|reg| reg.set_dmaen(true));
+        let _on_drop = OnDrop::new(|| {
+            stop_conversion(T::regs());
+            T::regs().cfgr1().modify(|reg| reg.set_dmaen(false));
+        });
+
+        let request = dma.request();
+        let mut transfer =
+            unsafe { Transfer::new_read(dma, request, T::regs().dr().as_ptr() as *mut u16, out, dma_options()) };
+
+        T::regs().cr().modify(|reg| reg.set_adstart(true));
+        (&mut transfer).await;
+
+        if transfer.has_error() || transfer.get_remaining_transfers() != 0 {
+            return Err(AdcError::Dma);
+        }
+
+        Ok(())
+    }
+
+    /// Read a pin, busy-waiting for the conversion instead of using the interrupt.
+    ///
+    /// This doesn't need an executor and leaves `ier` untouched, so it can be mixed freely with
+    /// the async methods. Like [`read`](Self::read), the correction set with
+    /// [`set_correction`](Self::set_correction) is applied. Fails with [`AdcError::Disabled`] if
+    /// the ADC is disabled.
+    pub fn blocking_read<P>(&mut self, pin: &mut P) -> Result<u16, AdcError>
+    where
+        P: AdcPin<T>,
+    {
+        let channel = pin.channel();
+        pin.configure_analog();
+        let raw = self.blocking_read_channel(channel)?;
+        Ok(self.correct(raw))
+    }
+
+    /// Like [`blocking_read`](Self::blocking_read), but gives up with [`AdcError::Timeout`] if the
+    /// conversion doesn't complete within `timeout`, e.g. because the ADC clock stopped.
+    ///
+    /// The conversion is then aborted like with [`read_timeout`](Self::read_timeout). Waiting for
+    /// the ADC to acknowledge the abort is bounded, so this returns even without an ADC clock; the
+    /// ADC is disabled in that case. Like `blocking_read`, fails with [`AdcError::Disabled`] if the
+    /// ADC is disabled.
+    #[cfg(feature = "time")]
+    pub fn blocking_read_timeout<P>(&mut self, pin: &mut P, timeout: Duration) -> Result<u16, AdcError>
+    where
+        P: AdcPin<T>,
+    {
+        let channel = pin.channel();
+        pin.configure_analog();
+        self.abort_pending();
+
+        T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+        Self::start_blocking_conversion(self.channel_sample_time(channel))?;
+
+        let deadline = Instant::now() + timeout;
+        while !T::regs().isr().read().eoc() {
+            if Instant::now() >= deadline {
+                abort_conversion(T::regs());
+                return Err(AdcError::Timeout);
+            }
+        }
+
+        let data = T::regs().dr().read().data();
+        end_conversion(T::regs());
+        Ok(self.correct(data))
+    }
+
+    /// Read an internal channel, busy-waiting for the conversion instead of using the interrupt.
+    ///
+    /// Fails with [`AdcError::Disabled`] if the ADC is disabled.
+    pub fn blocking_read_internal(&mut self, channel: &mut impl InternalChannel<T>) -> Result<u16, AdcError> {
+        let channel = channel.channel();
+        self.abort_pending();
+
+        T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+        self.blocking_convert(self.internal_sample_time(channel))
+    }
+
+    /// Read `pin` with the lowest latency the driver can give, for closed control loops.
+    ///
+    /// This busy-polls like [`blocking_read`](Self::blocking_read), but always uses the shortest
+    /// sample time of 1.5 cycles, ignoring the configured one. Set the resolution with
+    /// [`set_resolution`](Self::set_resolution) beforehand: at [`Resolution::SixBit`] a conversion
+    /// takes 1.5 + 6.5 = 8 ADC clock cycles, about 0.6 µs with the 14 MHz clock, against 14 cycles at
+    /// 12 bits. On top of that come a few register accesses, and whatever interrupts preempt the
+    /// polling. The total latency hasn't been characterized yet; the `adc_fast` example of the
+    /// STM32F0 measures the average and worst case on a given board.
+    ///
+    /// The short sample time needs a low impedance source, see the datasheet for the maximum.
+    /// Fails with [`AdcError::Disabled`] if the ADC is disabled.
+    pub fn read_fast<P>(&mut self, pin: &mut P) -> Result<u16, AdcError>
+    where
+        P: AdcPin<T>,
+    {
+        let channel = pin.channel();
+        pin.configure_analog();
+        self.abort_pending();
+
+        T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+        self.blocking_convert(SampleTime::Cycles1_5)
+    }
+
+    /// Read an external or internal channel.
+    ///
+    /// Pins are put into analog mode as with [`read`](Self::read). Internal channels are enabled,
+    /// waiting for them to start up, if they aren't yet, and left enabled; reading a number that's
+    /// not an internal channel fails with [`AdcError::InvalidChannel`].
+    #[cfg(feature = "time")]
+    pub async fn read_channel_enum(&mut self, channel: AdcChannel<'_, T>) -> Result<u16, AdcError> {
+        match channel {
+            AdcChannel::Pin(pin) => {
+                pin.configure_analog();
+                self.read_channel(pin.channel()).await
+            }
+            AdcChannel::Internal(channel) => {
+                if !Self::channel_is_internal(channel) {
+                    return Err(AdcError::InvalidChannel);
+                }
+
+                // The markers are forgotten to leave the channels enabled. That keeps them counted,
+                // so only take one if the channel is off, not on every read.
+                let ccr = T::regs().ccr().read();
+                if channel == VREF_CHANNEL && !ccr.vrefen() {
+                    core::mem::forget(self.enable_vref_async().await);
+                } else if channel == TEMPERATURE_CHANNEL && !ccr.tsen() {
+                    core::mem::forget(self.enable_temperature_async().await);
+                }
+                #[cfg(not(stm32l0))]
+                if channel == VBAT_CHANNEL && !ccr.vbaten() {
+                    core::mem::forget(Vbat::<T>::acquire());
+                }
+
+                self.read_raw_channel(channel).await
+            }
+        }
+    }
+
+    /// Read a channel by its number, for when the channels are only known at runtime.
+    ///
+    /// This skips the typed pin layer, so the caller has to put the pin of an external channel
+    /// into analog mode, or enable an internal channel, first. It doesn't need to be done again
+    /// for every read. Internal channels are sampled for at least as long as the datasheet
+    /// requires, like with [`read_internal`](Self::read_internal).
+    ///
+    /// Fails with [`AdcError::InvalidChannel`] if the ADC has no such channel.
+    pub async fn read_raw_channel(&mut self, channel: u8) -> Result<u16, AdcError> {
+        if channel >= Self::channel_count() {
+            return Err(AdcError::InvalidChannel);
+        }
+        self.abort_pending();
+
+        T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+        self.convert(self.internal_sample_time(channel)).await
+    }
+
+    /// Busy-polling version of [`read_raw_channel`](Self::read_raw_channel).
+    pub fn blocking_read_raw_channel(&mut self, channel: u8) -> Result<u16, AdcError> {
+        if channel >= Self::channel_count() {
+            return Err(AdcError::InvalidChannel);
+        }
+        self.abort_pending();
+
+        T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+        self.blocking_convert(self.internal_sample_time(channel))
+    }
+
+    /// Convert without the interrupt, having the executor poll `EOC` instead.
+    ///
+    /// The future wakes itself every time it's polled, so the executor keeps polling it until the
+    /// conversion is done, while still running other tasks in between.
+    async fn convert_polled(&mut self, sample_time: SampleTime) -> u16 {
+        Self::check_enabled().expect("ADC is disabled");
+
+        T::regs().isr().modify(|reg| {
+            reg.set_eoc(true);
+            reg.set_eosmp(true);
+            reg.set_ovr(true);
+        });
+
+        T::regs().smpr().modify(|reg| reg.set_smp(sample_time.into()));
+        T::regs().cr().modify(|reg| reg.set_adstart(true));
+
+        let on_drop = OnDrop::new(|| stop_conversion(T::regs()));
+
+        poll_fn(|cx| {
+            if T::regs().isr().read().eoc() {
+                Poll::Ready(())
+            } else {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+        .await;
+
+        on_drop.defuse();
+        let data = T::regs().dr().read().data();
+        end_conversion(T::regs());
+        data
+    }
+
+    /// Read `pin` and the internal reference back-to-back, in a single sequence.
+    ///
+    /// Returns `(signal, vrefint)`. Both samples are taken within a few microseconds of each other,
+    /// so they see the same VDDA, and `signal` can be scaled with [`vdda_mv`](Self::vdda_mv) of
+    /// `vrefint` without supply ripple between two separate reads showing up in the result. Both
+    /// channels use the longer of their sample times.
+    ///
+    /// This busy-polls, like [`blocking_read`](Self::blocking_read): the sequence is too short for
+    /// waking a task in between to be worth it.
+    pub fn read_ratiometric<P>(&mut self, pin: &mut P, _vref: &mut Vref<T>) -> (u16, u16)
+    where
+        P: AdcPin<T>,
+    {
+        Self::check_enabled().expect("ADC is disabled");
+        let channel = pin.channel();
+        pin.configure_analog();
+        self.abort_pending();
+        // `wait` can't be written while converting.
+        stop_conversion(T::regs());
+
+        let sample_time = self
+            .channel_sample_time(channel)
+            .max(self.internal_sample_time(VREF_CHANNEL));
+
+        // Sequences are converted in ascending channel order, so the pin always comes first.
+        T::regs().chselr().write(|reg| {
+            reg.set_chselx(channel as usize, true);
+            reg.set_chselx(VREF_CHANNEL as usize, true);
+        });
+        T::regs().smpr().modify(|reg| reg.set_smp(sample_time.into()));
+        T::regs().isr().write(|reg| {
+            reg.set_eoc(true);
+            reg.set_eosmp(true);
+            reg.set_eos(true);
+            reg.set_ovr(true);
+        });
+
+        // With WAIT, the second conversion only starts once the first sample has been read, so an
+        // interrupt preempting the polling can delay it but never make it overrun.
+        let wait = T::regs().cfgr1().read().wait();
+        T::regs().cfgr1().modify(|reg| reg.set_wait(true));
+        T::regs().cr().modify(|reg| reg.set_adstart(true));
+
+        while !T::regs().isr().read().eoc() {}
+        let signal = T::regs().dr().read().data();
+        while !T::regs().isr().read().eoc() {}
+        let vrefint = T::regs().dr().read().data();
+        end_conversion(T::regs());
+
+        T::regs().isr().write(|reg| {
+            reg.set_eosmp(true);
+            reg.set_eos(true);
+        });
+        T::regs().cfgr1().modify(|reg| reg.set_wait(wait));
+
+        (signal, vrefint)
+    }
+
+    fn blocking_convert(&mut self, sample_time: SampleTime) -> Result<u16, AdcError> {
+        Self::start_blocking_conversion(sample_time)?;
+
+        while !T::regs().isr().read().eoc() {}
+
+        let data = T::regs().dr().read().data();
+        end_conversion(T::regs());
+        Ok(data)
+    }
+
+    /// Start a conversion that's polled for rather than waited on with the interrupt.
+    fn start_blocking_conversion(sample_time: SampleTime) -> Result<(), AdcError> {
+        Self::check_enabled()?;
+
+        T::regs().isr().modify(|reg| {
+            reg.set_eoc(true);
+            reg.set_eosmp(true);
+            reg.set_ovr(true);
+        });
+
+        T::regs().smpr().modify(|reg| reg.set_smp(sample_time.into()));
+        T::regs().cr().modify(|reg| reg.set_adstart(true));
+
+        Ok(())
+    }
+
+    fn blocking_read_channel(&mut self, channel: u8) -> Result<u16, AdcError> {
+        self.abort_pending();
+
+        T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+
+        self.blocking_convert(self.channel_sample_time(channel))
+    }
+
+    /// Like [`read_sequence`](Self::read_sequence), but into a buffer of the driver, returning one
+    /// sample per channel of `sequence`.
+    ///
+    /// The capacity `N` can't be larger than the number of channels of the ADC,
+    /// [`channel_count`](Self::channel_count), which is checked at compile time. A sequence with
+    /// more than `N` channels fails with [`AdcError::LengthMismatch`].
+    pub async fn read_sequence_owned<const N: usize>(
+        &mut self,
+        dma: &mut impl RxDma<T>,
+        sequence: &AdcSequence,
+    ) -> Result<heapless::Vec<u16, N>, AdcError> {
+        let () = SequenceCapacity::<N>::CHECK;
+        if sequence.len() > N {
+            return Err(AdcError::LengthMismatch);
+        }
+
+        let mut buf = [0; N];
+        let out = &mut buf[..sequence.len()];
+        self.read_sequence(dma, sequence, out).await?;
+
+        Ok(out.iter().copied().collect())
+    }
+
+    /// Convert `channels` one after the other, calling `f(channel, sample)` as each completes.
+    ///
+    /// Unlike [`read_sequence`](Self::read_sequence), this needs neither DMA nor an output buffer,
+    /// and the channels are converted in the order given. The GPIOs of external channels have to
+    /// be in analog mode already, as for [`read_raw_channel`](Self::read_raw_channel).
+    ///
+    /// Dropping the future stops the ongoing conversion; `f` has then been called for the channels
+    /// converted so far.
+    pub async fn read_sequence_with(&mut self, channels: &[u8], mut f: impl FnMut(u8, u16)) -> Result<(), AdcError> {
+        self.abort_pending();
+
+        for &channel in channels {
+            debug_check_channel(channel);
+
+            T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+            let sample = self.convert(self.internal_sample_time(channel)).await?;
+            f(channel, sample);
+        }
+
+        Ok(())
+    }
+
+    /// Convert `channels` in a single sequence and return the result of the last one.
+    ///
+    /// The ADC scans in ascending order, so the result is that of the highest channel. Only the end
+    /// of the sequence wakes the task, the intermediate results are discarded. This is useful for
+    /// letting the sampling capacitor settle on high impedance sources with a few dummy
+    /// conversions of a neighbouring channel.
+    ///
+    /// Fails with [`AdcError::InvalidChannel`] if the ADC has no such channel. Dropping the future
+    /// stops the sequence.
+    pub async fn read_sequence_last(&mut self, channels: &[u8]) -> Result<u16, AdcError> {
+        Self::check_enabled()?;
+        assert!(!channels.is_empty());
+        if channels.iter().any(|&channel| channel as usize >= CHANNEL_COUNT) {
+            return Err(AdcError::InvalidChannel);
+        }
+        self.abort_pending();
+
+        let sample_time = channels
+            .iter()
+            .map(|&channel| self.channel_sample_time(channel))
+            .max()
+            .unwrap();
+
+        T::regs().chselr().write(|reg| {
+            for &channel in channels {
+                reg.set_chselx(channel as usize, true);
+            }
+        });
+        T::regs().smpr().modify(|reg| reg.set_smp(sample_time.into()));
+        T::regs().isr().modify(|reg| {
+            reg.set_eoc(true);
+            reg.set_eosmp(true);
+            reg.set_eos(true);
+            reg.set_ovr(true);
+        });
+
+        // The intermediate results are never read, let them overwrite each other.
+        T::regs().cfgr1().modify(|reg| reg.set_ovrmod(true));
+        let _on_drop = OnDrop::new(|| {
+            // `cfgr1` can only be written once the sequence is stopped.
+            stop_conversion(T::regs());
+            T::regs().ier().modify(|w| w.set_eosie(false));
+            T::regs().cfgr1().modify(|reg| reg.set_ovrmod(false));
+        });
+
+        T::regs().ier().modify(|w| w.set_eosie(true));
+        T::regs().cr().modify(|reg| reg.set_adstart(true));
+
+        poll_fn(|cx| {
+            T::state().waker.register(cx.waker());
+
+            if T::regs().isr().read().eos() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+
+        let data = T::regs().dr().read().data();
+        end_conversion(T::regs());
+        T::regs().isr().write(|reg| {
+            reg.set_eos(true);
+            reg.set_ovr(true);
+        });
+
+        Ok(data)
+    }
+
+    /// Start scanning `channels` continuously into `buffer`, using DMA in circular mode.
+    ///
+    /// Samples are stored in ascending channel order, one full scan after the other. Use
+    /// [`RingBufferedAdc::read`] to take them out before the DMA wraps around and overwrites
+    /// them. Dropping the returned handle stops the DMA and the ADC.
+    ///
+    /// As with [`read_sequence`](Self::read_sequence), the ADC interrupts stay disabled. A DMA
+    /// transfer error stops the acquisition and is reported as [`AdcError::Dma`] by
+    /// [`RingBufferedAdc::read`].
+    pub fn start_ring<'a, D: RxDma<T>>(
+        &'a mut self,
+        dma: &'a mut D,
+        channels: &[u8],
+        buffer: &'static mut [u16],
+    ) -> RingBufferedAdc<'a, 'd, T, D> {
+        Self::check_enabled().expect("ADC is disabled");
+        Self::check_not_discontinuous();
+        assert!(!channels.is_empty());
+        self.abort_pending();
+
+        let sample_time = channels
+            .iter()
+            .map(|&channel| self.channel_sample_time(channel))
+            .max()
+            .unwrap();
+
+        stop_conversion(T::regs());
+        T::regs().chselr().write(|reg| {
+            for &channel in channels {
+                reg.set_chselx(channel as usize, true);
+            }
+        });
+        T::regs().smpr().modify(|reg| reg.set_smp(sample_time.into()));
+        T::regs().isr().modify(|reg| {
+            reg.set_eoc(true);
+            reg.set_eosmp(true);
+            reg.set_ovr(true);
+        });
+        // See `read_sequence`.
+        T::regs().ier().write(|_| {});
+
+        let request = dma.request();
+        let mut ring_buf = unsafe {
+            ReadableRingBuffer::new_read(dma, request, T::regs().dr().as_ptr() as *mut u16, buffer, dma_options())
+        };
+        ring_buf.start();
+
+        T::regs().cfgr1().modify(|reg| {
+            reg.set_cont(true);
+            reg.set_dmacfg(Dmacfg::CIRCULAR);
+            reg.set_dmaen(true);
+        });
+        T::regs().cr().modify(|reg| reg.set_adstart(true));
+
+        RingBufferedAdc { adc: self, ring_buf }
+    }
+
+    /// Poll a conversion of `channel` without awaiting it.
+    ///
+    /// The first call starts the conversion and returns `None`, as do further calls until it has
+    /// completed. The call after that returns the sample, and the next one starts a new conversion.
+    /// `None` is also returned while a conversion of another channel started here is in flight, and
+    /// when the ADC is disabled. No interrupt is used, so the caller has to poll again by itself.
+    ///
+    /// Reading a single channel in another way, like with [`read`](Self::read) or
+    /// [`blocking_read`](Self::blocking_read), aborts a conversion started here.
+    pub fn try_read_channel(&mut self, channel: u8) -> Option<u16> {
+        match self.pending_channel {
+            Some(pending) if pending == channel => {
+                if !T::regs().isr().read().eoc() {
+                    return None;
+                }
+
+                self.pending_channel = None;
+                let data = T::regs().dr().read().data();
+                end_conversion(T::regs());
+                Some(data)
+            }
+            Some(_) => None,
+            None => {
+                Self::check_enabled().ok()?;
+
+                T::regs().isr().modify(|reg| {
+                    reg.set_eoc(true);
+                    reg.set_eosmp(true);
+                    reg.set_ovr(true);
+                });
+
+                T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+                T::regs()
+                    .smpr()
+                    .modify(|reg| reg.set_smp(self.channel_sample_time(channel).into()));
+                T::regs().cr().modify(|reg| reg.set_adstart(true));
+
+                self.pending_channel = Some(channel);
+                None
+            }
+        }
+    }
+
+    /// Whether a conversion started by [`try_read_channel`](Self::try_read_channel) is still
+    /// running.
+    pub fn conversion_in_progress(&self) -> bool {
+        self.pending_channel.is_some() && !T::regs().isr().read().eoc()
+    }
+
+    /// Abort the conversion started by [`try_read_channel`](Self::try_read_channel), if any.
+    fn abort_pending(&mut self) {
+        if self.pending_channel.take().is_some() {
+            stop_conversion(T::regs());
+        }
+    }
+
+    async fn convert(&mut self, sample_time: SampleTime) -> Result<u16, AdcError> {
+        Self::check_enabled()?;
+
+        T::regs().isr().modify(|reg| {
+            reg.set_eoc(true);
+            reg.set_eosmp(true);
+            reg.set_ovr(true);
+        });
+
+        T::regs().smpr().modify(|reg| reg.set_smp(sample_time.into()));
+        T::regs().ier().modify(|w| {
+            w.set_eocie(true);
+            w.set_ovrie(true);
+        });
+        T::regs().cr().modify(|reg| reg.set_adstart(true));
+
+        // Make the read cancel-safe: if the future is dropped, e.g. by `select` or
+        // `with_timeout`, stop the conversion so that its result can't be mistaken for a later one.
+        let on_drop = OnDrop::new(|| abort_conversion(T::regs()));
+
+        poll_fn(|cx| {
+            T::state().waker.register(cx.waker());
+
+            let isr = T::regs().isr().read();
+            if isr.eoc() || isr.ovr() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+
+        on_drop.defuse();
+
+        let data = T::regs().dr().read().data();
+        end_conversion(T::regs());
+
+        if T::regs().isr().read().ovr() {
+            T::regs().isr().write(|reg| reg.set_ovr(true));
+            return Err(AdcError::Overrun);
+        }
+
+        Ok(data)
+    }
+
+    async fn read_channel(&mut self, channel: u8) -> Result<u16, AdcError> {
+        self.abort_pending();
+
+        // A.7.5 Single conversion sequence code example - Software trigger
+        T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+
+        self.convert(self.channel_sample_time(channel)).await
+    }
+}
+
+/// An [`Adc`] shared between several tasks.
+///
+/// Each read locks the ADC for the duration of a single conversion, so the tasks take turns
+/// instead of one of them holding the ADC. Put it in a `static` to share it between tasks, using a `RawMutex`
+/// matching where the tasks run: e.g. `ThreadModeRawMutex` for tasks of the same thread-mode
+/// executor.
+pub struct AdcShared<'d, M: RawMutex, T: Instance> {
+    adc: Mutex<M, Adc<'d, T>>,
+}
+
+impl<'d, M: RawMutex, T: Instance> AdcShared<'d, M, T> {
+    pub fn new(adc: Adc<'d, T>) -> Self {
+        Self { adc: Mutex::new(adc) }
+    }
+
+    /// Read a pin, waiting for other tasks to finish their reads first.
+    pub async fn read<P>(&self, pin: &mut P) -> Result<u16, AdcError>
+    where
+        P: AdcPin<T>,
+    {
+        self.adc.lock().await.read(pin).await
+    }
+
+    /// Read an internal channel, waiting for other tasks to finish their reads first.
+    pub async fn read_internal(&self, channel: &mut impl InternalChannel<T>) -> Result<u16, AdcError> {
+        self.adc.lock().await.read_internal(channel).await
+    }
+
+    /// Lock the ADC for exclusive use, e.g. to change its configuration.
+    pub async fn lock(&self) -> MutexGuard<'_, M, Adc<'d, T>> {
+        self.adc.lock().await
+    }
+}
+
+/// Blocking adapter implementing the `embedded-hal` ADC traits.
+///
+/// The embedded-hal 1.0 release no longer has ADC traits, so this implements the 0.2
+/// [`OneShot`](embedded_hal_02::adc::OneShot) trait, using the busy-polling reads. Prefer the
+/// async methods of [`Adc`] when not interfacing with such a driver.
+pub struct BlockingAdc<'d, T: Instance> {
+    adc: Adc<'d, T>,
+    interrupt_enabled: bool,
+}
+
+impl<'d, T: Instance> BlockingAdc<'d, T> {
+    pub fn new(adc: Adc<'d, T>) -> Self {
+        Self {
+            adc,
+            interrupt_enabled: true,
+        }
+    }
+
+    /// Get back the wrapped [`Adc`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if this was created with [`Adc::new_blocking`], use [`into_async`](Self::into_async)
+    /// to bind the interrupt in that case.
+    pub fn into_inner(self) -> Adc<'d, T> {
+        assert!(self.interrupt_enabled, "ADC interrupt is not bound, use `into_async`");
+        self.adc
+    }
+
+    /// Get back the wrapped [`Adc`], enabling its interrupt so the async methods can be used.
+    pub fn into_async(
+        self,
+        _irq: impl interrupt::typelevel::Binding<T::Interrupt, InterruptHandler<T>> + 'd,
+    ) -> Adc<'d, T> {
+        if !self.interrupt_enabled {
+            enable_interrupt::<T>();
+        }
+        self.adc
+    }
+
+    /// The wrapped [`Adc`], for its configuration getters and internal channel setup.
+    pub fn adc(&self) -> &Adc<'d, T> {
+        &self.adc
+    }
+
+    /// Set the sample time, see [`Adc::set_sample_time`].
+    pub fn set_sample_time(&mut self, sample_time: SampleTime) {
+        self.adc.set_sample_time(sample_time);
+    }
+
+    /// Set the resolution, see [`Adc::set_resolution`].
+    pub fn set_resolution(&mut self, resolution: Resolution) -> Result<(), AdcError> {
+        self.adc.set_resolution(resolution)
+    }
+
+    /// Read a pin, busy-polling until the conversion is done.
+    pub fn read<P>(&mut self, pin: &mut P) -> Result<u16, AdcError>
+    where
+        P: AdcPin<T>,
+    {
+        self.adc.blocking_read(pin)
+    }
+
+    /// Read an internal channel, busy-polling until the conversion is done.
+    pub fn read_internal(&mut self, channel: &mut impl InternalChannel<T>) -> Result<u16, AdcError> {
+        self.adc.blocking_read_internal(channel)
+    }
+
+    /// Read a pin asynchronously, without an interrupt.
+    ///
+    /// Instead of being woken by the ADC interrupt, the read wakes itself and is polled again on
+    /// every executor iteration until the conversion is done, so other tasks keep running in the
+    /// meantime. This has a cost: the sample is picked up as late as one round of the other ready
+    /// tasks, and the executor never sleeps while a read is pending, so it draws more power than
+    /// the interrupt-driven [`Adc::read`]. Use it when there's no interrupt vector left for the
+    /// ADC.
+    ///
+    /// Like the other reads, this is cancel-safe. The correction of [`Adc::set_correction`] is
+    /// applied.
+    pub async fn read_polled<P>(&mut self, pin: &mut P) -> u16
+    where
+        P: AdcPin<T>,
+    {
+        let channel = pin.channel();
+        pin.configure_analog();
+        self.adc.abort_pending();
+
+        T::regs().chselr().write(|reg| reg.set_chselx(channel as usize, true));
+        let sample_time = self.adc.channel_sample_time(channel);
+        let raw = self.adc.convert_polled(sample_time).await;
+        self.adc.correct(raw)
+    }
+}
+
+impl<'d, T, P> embedded_hal_02::adc::OneShot<T, u16, P> for BlockingAdc<'d, T>
+where
+    T: Instance,
+    P: embedded_hal_02::adc::Channel<T> + AdcPin<T>,
+{
+    type Error = AdcError;
+
+    fn read(&mut self, pin: &mut P) -> nb::Result<u16, Self::Error> {
+        Ok(BlockingAdc::read(self, pin)?)
+    }
+}
+
+/// Continuous DMA acquisition into a ring buffer, created by [`Adc::start_ring`].
+pub struct RingBufferedAdc<'a, 'd, T: Instance, D: RxDma<T>> {
+    adc: &'a mut Adc<'d, T>,
+    ring_buf: ReadableRingBuffer<'a, D, u16>,
+}
+
+impl<'a, 'd, T: Instance, D: RxDma<T>> RingBufferedAdc<'a, 'd, T, D> {
+    /// Copy the samples taken since the last call into `out`, returning how many were copied.
+    ///
+    /// Fails with [`AdcError::Overrun`] if the DMA overwrote samples before they were read, and
+    /// with [`AdcError::Dma`] if a DMA transfer error stopped the acquisition.
+    pub fn read(&mut self, out: &mut [u16]) -> Result<usize, AdcError> {
+        if self.ring_buf.has_error() {
+            return Err(AdcError::Dma);
+        }
+        match self.ring_buf.read(out) {
+            Ok((len, _)) => Ok(len),
+            Err(_) => Err(AdcError::Overrun),
+        }
+    }
+
+    /// Wait until `out` can be filled completely, then fill it.
+    ///
+    /// Fails like [`read`](Self::read), in which case the content of `out` is unspecified.
+    pub async fn read_exact(&mut self, out: &mut [u16]) -> Result<(), AdcError> {
+        let mut filled = 0;
+        poll_fn(|cx| {
+            // The DMA interrupt wakes us on half transfer, transfer complete and transfer error.
+            self.ring_buf.set_waker(cx.waker());
+            filled += self.read(&mut out[filled..])?;
+            if filled == out.len() {
+                Poll::Ready(Ok(()))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Stop the acquisition and give back the [`Adc`] for one-shot reads.
+    ///
+    /// This is the same as dropping the handle, but keeps the `Adc` borrow usable in the same
+    /// scope. Samples still in the buffer are discarded.
+    pub fn stop(self) -> &'a mut Adc<'d, T> {
+        Self::halt();
+
+        let mut this = core::mem::ManuallyDrop::new(self);
+        // Safety: `this` is never used or dropped again, so each field is moved out only once.
+        unsafe {
+            core::ptr::drop_in_place(&mut this.ring_buf);
+            core::ptr::read(&this.adc)
+        }
+    }
+
+    fn halt() {
+        stop_conversion(T::regs());
+        T::regs().cfgr1().modify(|reg| {
+            reg.set_cont(false);
+            reg.set_dmacfg(Dmacfg::ONESHOT);
+            reg.set_dmaen(false);
+        });
+        T::regs().isr().write(|reg| {
+            reg.set_eoc(true);
+            reg.set_eosmp(true);
+            reg.set_eos(true);
+            reg.set_ovr(true);
+        });
+
+        // The DMA channel itself is stopped when `ring_buf` is dropped.
+    }
+}
+
+impl<'a, 'd, T: Instance, D: RxDma<T>> Drop for RingBufferedAdc<'a, 'd, T, D> {
+    fn drop(&mut self) {
+        Self::halt();
+    }
+}
+
+/// Continuous conversion of a single channel, created by [`Adc::into_continuous`].
+pub struct ContinuousAdc<'a, 'd, T: Instance> {
+    adc: &'a mut Adc<'d, T>,
+}
+
+impl<'a, 'd, T: Instance> ContinuousAdc<'a, 'd, T> {
+    /// Stop the conversions and give back the [`Adc`] for one-shot reads.
+    ///
+    /// This is the same as dropping the stream, but keeps the `Adc` borrow usable in the same
+    /// scope. A sample not taken out of the stream yet is discarded.
+    pub fn stop(self) -> &'a mut Adc<'d, T> {
+        Self::halt();
+
+        let this = core::mem::ManuallyDrop::new(self);
+        // Safety: `this` is never used or dropped again.
+        unsafe { core::ptr::read(&this.adc) }
+    }
+
+    fn halt() {
+        // Keep the interrupt handler from storing another sample while stopping.
+        T::regs().ier().modify(|w| {
+            w.set_eocie(false);
+            w.set_ovrie(false);
+        });
+        stop_conversion(T::regs());
+
+        T::regs().cfgr1().modify(|reg| reg.set_cont(false));
+        T::regs().isr().write(|reg| {
+            reg.set_eoc(true);
+            reg.set_eosmp(true);
+            reg.set_ovr(true);
+        });
+
+        let state = T::state();
+        state.continuous.store(false, Ordering::Release);
+        state.sample_ready.store(false, Ordering::Relaxed);
+        state.overrun.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Yields [`AdcError::Overrun`] in place of the samples lost when the interrupt handler fell
+/// behind the ADC, and keeps going afterwards.
+impl<'a, 'd, T: Instance> Stream for ContinuousAdc<'a, 'd, T> {
+    type Item = Result<u16, AdcError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let state = T::state();
+        state.waker.register(cx.waker());
+
+        if state.overrun.swap(false, Ordering::Acquire) {
+            Poll::Ready(Some(Err(AdcError::Overrun)))
+        } else if state.sample_ready.swap(false, Ordering::Acquire) {
+            Poll::Ready(Some(Ok(state.sample.load(Ordering::Relaxed))))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<'a, 'd, T: Instance> Drop for ContinuousAdc<'a, 'd, T> {
+    fn drop(&mut self) {
+        Self::halt();
+    }
+}
+
+/// A fixed number of conversions of a single channel, created by [`Adc::burst_stream`].
+pub struct BurstStream<'a, 'd, T: Instance> {
+    _adc: &'a mut Adc<'d, T>,
+    remaining: usize,
+    converting: bool,
+}
+
+impl<'a, 'd, T: Instance> BurstStream<'a, 'd, T> {
+    fn halt() {
+        stop_conversion(T::regs());
+        T::regs().ier().modify(|w| w.set_eocie(false));
+        T::regs().isr().write(|reg| {
+            reg.set_eoc(true);
+            reg.set_eosmp(true);
+            reg.set_ovr(true);
+        });
+    }
+}
+
+impl<'a, 'd, T: Instance> Stream for BurstStream<'a, 'd, T> {
+    type Item = u16;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.remaining == 0 {
+            return Poll::Ready(None);
+        }
+
+        if !this.converting {
+            T::regs().isr().write(|reg| {
+                reg.set_eoc(true);
+                reg.set_eosmp(true);
+                reg.set_ovr(true);
+            });
+            T::regs().ier().modify(|w| w.set_eocie(true));
+            T::regs().cr().modify(|reg| reg.set_adstart(true));
+            this.converting = true;
+        }
+
+        T::state().waker.register(cx.waker());
+        if !T::regs().isr().read().eoc() {
+            return Poll::Pending;
+        }
+
+        this.converting = false;
+        this.remaining -= 1;
+        Poll::Ready(Some(T::regs().dr().read().data()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, 'd, T: Instance> Drop for BurstStream<'a, 'd, T> {
+    fn drop(&mut self) {
+        Self::halt();
+    }
+}
+
+/// How many times the ADC is polled for acknowledging a stop or disable, or for completing the
+/// calibration, before giving up on it, which is several milliseconds.
+const DROP_SPIN_LIMIT: u32 = 100_000;
+
+/// Poll `done` until it returns true, or [`DROP_SPIN_LIMIT`] times. Returns whether it did.
+fn spin_until(mut done: impl FnMut() -> bool) -> bool {
+    (0..DROP_SPIN_LIMIT).any(|_| done())
+}
+
+/// Disable the ADC and stop its peripheral clock, for [`Adc::free`] and `Drop`.
+fn shut_down<T: Instance>() {
+    // A.7.3 ADC disable code example
+    //
+    // Without an ADC clock, e.g. if HSI14 was turned off first, the ADC never acknowledges
+    // ADSTP and ADDIS. Don't hang in that case: the peripheral clock is stopped either way.
+    //
+    // A conversion still running, e.g. a hardware triggered one, is aborted and its result lost.
+    stop_conversion(T::regs());
+
+    // ADDIS must only be set while the ADC is enabled, e.g. not after `Adc::disable`.
+    if T::regs().cr().read().aden() {
+        T::regs().cr().modify(|reg| reg.set_addis(true));
+        if !spin_until(|| !T::regs().cr().read().aden()) {
+            warn!("ADC didn't acknowledge being disabled");
+        }
+    }
+
+    T::disable();
+    T::state().claimed.store(false, Ordering::Relaxed);
+}
+
+impl<'d, T: Instance> Drop for Adc<'d, T> {
+    fn drop(&mut self) {
+        shut_down::<T>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peripherals::ADC;
+
+    #[test]
+    fn full_scale_count_matches_resolution() {
+        let table = [
+            (Resolution::TwelveBit, 0x0FFF, 0xFFF0),
+            (Resolution::TenBit, 0x03FF, 0xFFC0),
+            (Resolution::EightBit, 0x00FF, 0xFF00),
+            (Resolution::SixBit, 0x003F, 0x00FC),
+        ];
+
+        for (resolution, right, left) in table {
+            assert_eq!(right, full_scale_count(resolution, DataAlign::Right));
+            assert_eq!(left, full_scale_count(resolution, DataAlign::Left));
+        }
+    }
+
+    #[test]
+    fn watchdog_threshold_is_12_bit() {
+        assert_eq!(0x0ABC, watchdog_threshold(0x0ABC, Resolution::TwelveBit));
+        assert_eq!(0x0FFC, watchdog_threshold(0x03FF, Resolution::TenBit));
+        assert_eq!(0x0800, watchdog_threshold(0x0080, Resolution::EightBit));
+        assert_eq!(0x0FC0, watchdog_threshold(0x003F, Resolution::SixBit));
+        // Out of range thresholds are clamped rather than shifted out.
+        assert_eq!(0x0FC0, watchdog_threshold(0xFFFF, Resolution::SixBit));
+        assert_eq!(0x0FFF, watchdog_threshold(0xFFFF, Resolution::TwelveBit));
+    }
+
+    #[test]
+    fn rounded_mean_rounds_to_nearest() {
+        assert_eq!(3, rounded_mean(5, 2));
+        assert_eq!(1, rounded_mean(4, 3));
+        assert_eq!(2, rounded_mean(5, 3));
+        // The largest sum `read_averaged` can accumulate.
+        assert_eq!(0xFFFF, rounded_mean(0xFFFF * u32::MAX as u64, u32::MAX));
+        assert_eq!(0xFFFF, rounded_mean(0xFFFF, 1));
+    }
+
+    #[test]
+    fn sequence_is_validated() {
+        let sequence = AdcSequence::new(&[17, 1, 4]).unwrap();
+        assert_eq!(3, sequence.len());
+        assert!(sequence.channels().eq([1, 4, 17]));
+
+        assert_eq!(
+            Err(AdcError::InvalidChannel),
+            AdcSequence::new(&[1, CHANNEL_COUNT as u8])
+        );
+        assert_eq!(Err(AdcError::DuplicateChannel), AdcSequence::new(&[4, 1, 4]));
+        assert!(AdcSequence::new(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn sample_scales_by_its_resolution() {
+        let sample = |value, resolution| Sample {
+            value,
+            resolution,
+            full_scale: full_scale_count(resolution, DataAlign::Right),
+        };
+
+        assert_eq!(3300, sample(4095, Resolution::TwelveBit).to_mv(3300));
+        assert_eq!(3300, sample(63, Resolution::SixBit).to_mv(3300));
+        assert_eq!(1650, sample(2048, Resolution::TwelveBit).to_mv(3300));
+        assert_eq!(4095, u16::from(sample(4095, Resolution::TwelveBit)));
+    }
+
+    #[test]
+    fn sample_to_mv_scales_by_vref() {
+        let vref_mv = VREF_INT_TYPICAL_MV as u16;
+
+        assert_eq!(vref_mv, sample_to_mv(1500, 1500, Resolution::TwelveBit));
+        assert_eq!(vref_mv * 2, sample_to_mv(3000, 1500, Resolution::TwelveBit));
+        assert_eq!(vref_mv * 2, sample_to_mv(48, 24, Resolution::SixBit));
+        assert_eq!(0, sample_to_mv(0, 1500, Resolution::TwelveBit));
+    }
+
+    #[test]
+    fn calibrated_mv_doesnt_overflow() {
+        // Sampled at the calibration VDDA, a full-scale sample is the calibration VDDA.
+        assert_eq!(3300, calibrated_mv(4095, 1500, 1500, 3300));
+        assert_eq!(1650, calibrated_mv(2048, 1500, 1500, 3300));
+        // An oversampled 16-bit full-scale sample and reference, where the numerator is ~2^40.
+        assert_eq!(3300, calibrated_mv(0xFFF0, 0xFFF0, 4095, 3300));
+        // VDDA at 3.0 V: the reference reads 1.1 times its calibration value.
+        assert_eq!(1500, calibrated_mv(2048, 1650, 1500, 3300));
+        assert_eq!(0, calibrated_mv(0, 1500, 1500, 3300));
+    }
 
-        T::disable();
+    #[test]
+    fn vref_int_typical_matches_family() {
+        #[cfg(stm32f0)]
+        assert_eq!(1230, Adc::<ADC>::vref_int_typical());
+        #[cfg(stm32l0)]
+        assert_eq!(1224, Adc::<ADC>::vref_int_typical());
     }
 }
@@ -5,7 +5,7 @@ use core::pin::Pin;
 use core::sync::atomic::{fence, Ordering};
 use core::task::{Context, Poll, Waker};
 
-use atomic_polyfill::AtomicUsize;
+use atomic_polyfill::{AtomicBool, AtomicUsize};
 use embassy_hal_internal::{into_ref, Peripheral, PeripheralRef};
 use embassy_sync::waitqueue::AtomicWaker;
 
@@ -28,6 +28,8 @@ pub struct TransferOptions {
     pub half_transfer_ir: bool,
     /// Enable transfer complete interrupt
     pub complete_transfer_ir: bool,
+    /// Report transfer errors through `has_error` instead of panicking in the interrupt handler
+    pub report_transfer_error: bool,
 }
 
 impl Default for TransferOptions {
@@ -36,6 +38,7 @@ impl Default for TransferOptions {
             circular: false,
             half_transfer_ir: false,
             complete_transfer_ir: true,
+            report_transfer_error: false,
         }
     }
 }
@@ -62,15 +65,20 @@ impl From<Dir> for vals::Dir {
 struct State {
     ch_wakers: [AtomicWaker; BDMA_CHANNEL_COUNT],
     complete_count: [AtomicUsize; BDMA_CHANNEL_COUNT],
+    report_error: [AtomicBool; BDMA_CHANNEL_COUNT],
+    error: [AtomicBool; BDMA_CHANNEL_COUNT],
 }
 
 impl State {
     const fn new() -> Self {
         const ZERO: AtomicUsize = AtomicUsize::new(0);
+        const FALSE: AtomicBool = AtomicBool::new(false);
         const AW: AtomicWaker = AtomicWaker::new();
         Self {
             ch_wakers: [AW; BDMA_CHANNEL_COUNT],
             complete_count: [ZERO; BDMA_CHANNEL_COUNT],
+            report_error: [FALSE; BDMA_CHANNEL_COUNT],
+            error: [FALSE; BDMA_CHANNEL_COUNT],
         }
     }
 }
@@ -118,7 +126,15 @@ pub(crate) unsafe fn on_irq_inner(dma: pac::bdma::Dma, channel_num: usize, index
     let cr = dma.ch(channel_num).cr();
 
     if isr.teif(channel_num) {
-        panic!("DMA: error on BDMA@{:08x} channel {}", dma.as_ptr() as u32, channel_num);
+        if !STATE.report_error[index].load(Ordering::Acquire) {
+            panic!("DMA: error on BDMA@{:08x} channel {}", dma.as_ptr() as u32, channel_num);
+        }
+
+        // The hardware already disabled the channel, so just record the error for the owner.
+        dma.ifcr().write(|w| w.set_teif(channel_num, true));
+        STATE.error[index].store(true, Ordering::Release);
+        STATE.ch_wakers[index].wake();
+        return;
     }
 
     if isr.htif(channel_num) && cr.read().htie() {
@@ -277,6 +293,8 @@ impl<'a, C: Channel> Transfer<'a, C> {
         let mut this = Self { channel };
         this.clear_irqs();
         STATE.complete_count[this.channel.index()].store(0, Ordering::Release);
+        STATE.error[this.channel.index()].store(false, Ordering::Release);
+        STATE.report_error[this.channel.index()].store(options.report_transfer_error, Ordering::Release);
 
         #[cfg(dmamux)]
         super::dmamux::configure_dmamux(&mut *this.channel, _request);
@@ -341,6 +359,12 @@ impl<'a, C: Channel> Transfer<'a, C> {
         ch.ndtr().read().ndt()
     }
 
+    /// Whether the transfer was stopped by a transfer error.
+    /// Only reported if `report_transfer_error` was set, the interrupt handler panics otherwise.
+    pub fn has_error(&self) -> bool {
+        STATE.error[self.channel.index()].load(Ordering::Acquire)
+    }
+
     pub fn blocking_wait(mut self) {
         while self.is_running() {}
         self.request_stop();
@@ -411,7 +435,7 @@ impl<'a, C: Channel, W: Word> ReadableRingBuffer<'a, C, W> {
         _request: Request,
         peri_addr: *mut W,
         buffer: &'a mut [W],
-        _options: TransferOptions,
+        options: TransferOptions,
     ) -> Self {
         into_ref!(channel);
 
@@ -449,6 +473,8 @@ impl<'a, C: Channel, W: Word> ReadableRingBuffer<'a, C, W> {
             ringbuf: ReadableDmaRingBuffer::new(buffer),
         };
         this.clear_irqs();
+        STATE.error[this.channel.index()].store(false, Ordering::Release);
+        STATE.report_error[this.channel.index()].store(options.report_transfer_error, Ordering::Release);
 
         #[cfg(dmamux)]
         super::dmamux::configure_dmamux(&mut *this.channel, _request);
@@ -531,6 +557,12 @@ impl<'a, C: Channel, W: Word> ReadableRingBuffer<'a, C, W> {
         let ch = self.channel.regs().ch(self.channel.num());
         ch.cr().read().en()
     }
+
+    /// Whether the DMA was stopped by a transfer error.
+    /// Only reported if `report_transfer_error` was set, the interrupt handler panics otherwise.
+    pub fn has_error(&self) -> bool {
+        STATE.error[self.channel.index()].load(Ordering::Acquire)
+    }
 }
 
 impl<'a, C: Channel, W: Word> Drop for ReadableRingBuffer<'a, C, W> {
@@ -140,6 +140,15 @@ impl<'d, T: Pin> Flex<'d, T> {
         });
     }
 
+    /// Put the pin into analog mode, e.g. for an ADC input.
+    ///
+    /// The pin stays in analog mode until it's explicitly put into another mode, like with
+    /// [`set_as_input`](Self::set_as_input).
+    #[inline]
+    pub fn set_as_analog(&mut self) {
+        self.pin.set_as_analog();
+    }
+
     #[inline]
     pub fn is_high(&self) -> bool {
         !self.is_low()
@@ -324,6 +333,30 @@ impl<'d, T: Pin> Input<'d, T> {
     }
 }
 
+/// GPIO analog driver.
+///
+/// Holds a pin in analog mode, e.g. for an ADC input that's configured once instead of on every
+/// read.
+pub struct Analog<'d, T: Pin> {
+    pub(crate) pin: Flex<'d, T>,
+}
+
+impl<'d, T: Pin> Analog<'d, T> {
+    #[inline]
+    pub fn new(pin: impl Peripheral<P = T> + 'd) -> Self {
+        let mut pin = Flex::new(pin);
+        pin.set_as_analog();
+        Self { pin }
+    }
+
+    #[inline]
+    pub fn degrade(self) -> Analog<'d, AnyPin> {
+        Analog {
+            pin: self.pin.degrade(),
+        }
+    }
+}
+
 /// Digital input or output level.
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -631,20 +664,26 @@ pub(crate) mod sealed {
             block.moder().modify(|w| w.set_moder(pin, vals::Moder::ALTERNATE));
         }
 
+        /// Put the pin into analog mode.
+        ///
+        /// The mode registers are shared by the whole port, so this is done in a critical section
+        /// to not race with other pins of the port being reconfigured.
         #[inline]
         fn set_as_analog(&self) {
             let pin = self._pin() as usize;
             let block = self.block();
-            #[cfg(gpio_v1)]
-            {
-                let crlh = if pin < 8 { 0 } else { 1 };
-                block.cr(crlh).modify(|w| {
-                    w.set_mode(pin % 8, vals::Mode::INPUT);
-                    w.set_cnf_in(pin % 8, vals::CnfIn::ANALOG);
-                });
-            }
-            #[cfg(gpio_v2)]
-            block.moder().modify(|w| w.set_moder(pin, vals::Moder::ANALOG));
+            critical_section::with(|_| {
+                #[cfg(gpio_v1)]
+                {
+                    let crlh = if pin < 8 { 0 } else { 1 };
+                    block.cr(crlh).modify(|w| {
+                        w.set_mode(pin % 8, vals::Mode::INPUT);
+                        w.set_cnf_in(pin % 8, vals::CnfIn::ANALOG);
+                    });
+                }
+                #[cfg(gpio_v2)]
+                block.moder().modify(|w| w.set_moder(pin, vals::Moder::ANALOG));
+            });
         }
 
         /// Set the pin as "disconnected", ie doing nothing and consuming the lowest
@@ -238,6 +238,7 @@ const DMA_TRANSFER_OPTIONS: crate::dma::TransferOptions = crate::dma::TransferOp
     circular: false,
     half_transfer_ir: false,
     complete_transfer_ir: true,
+    report_transfer_error: false,
 };
 
 /// SDMMC configuration
@@ -81,9 +81,11 @@ impl Duration {
         }
     }
 
-    /// Creates a duration corresponding to the specified Hz.
+    /// Creates a duration corresponding to the specified Hz, i.e. the period of that frequency,
+    /// rounded to the nearest tick. `Duration::from_hz(1000)` is the period of a 1 kHz loop.
     /// NOTE: Giving this function a hz >= the TICK_HZ of your platform will clamp the Duration to 1
     /// tick. Doing so will not deadlock, but will certainly not produce the desired output.
+    /// Panics if `hz` is zero.
     pub const fn from_hz(hz: u64) -> Duration {
         let ticks = {
             if hz >= TICK_HZ {
@@ -208,3 +210,21 @@ impl From<Duration> for core::time::Duration {
         core::time::Duration::from_micros(value.as_micros())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_hz_is_the_period() {
+        assert_eq!(Duration::from_secs(1), Duration::from_hz(1));
+        // Rounded to the nearest tick, e.g. 33 ticks at 32768 Hz.
+        assert_eq!(Duration::from_ticks((TICK_HZ + 500) / 1000), Duration::from_hz(1000));
+    }
+
+    #[test]
+    fn from_hz_saturates_at_one_tick() {
+        assert_eq!(Duration::from_ticks(1), Duration::from_hz(TICK_HZ));
+        assert_eq!(Duration::from_ticks(1), Duration::from_hz(u64::MAX));
+    }
+}
@@ -19,23 +19,22 @@ async fn main(_spawner: Spawner) {
     let p = embassy_stm32::init(Default::default());
     info!("Hello World!");
 
-    let mut adc = Adc::new(p.ADC, Irqs, &mut Delay);
-    adc.set_sample_time(SampleTime::Cycles71_5);
+    let mut adc = Adc::builder(p.ADC, Irqs)
+        .sample_time(SampleTime::Cycles71_5)
+        .build(&mut Delay);
     let mut pin = p.PA1;
 
     let mut vrefint = adc.enable_vref(&mut Delay);
-    let vrefint_sample = adc.read_internal(&mut vrefint).await;
-    let convert_to_millivolts = |sample| {
-        // From https://www.st.com/resource/en/datasheet/stm32f031c6.pdf
-        // 6.3.4 Embedded reference voltage
-        const VREFINT_MV: u32 = 1230; // mV
+    let vrefint_sample = adc.read_internal(&mut vrefint).await.unwrap();
+    let vdda_mv = adc.vdda_mv(vrefint_sample);
+    info!("VDDA: {} mV", vdda_mv);
 
-        (u32::from(sample) * VREFINT_MV / u32::from(vrefint_sample)) as u16
-    };
+    let mut temperature = adc.enable_temperature(&mut Delay);
+    unwrap!(adc.temperature_sanity_check(&mut temperature, &mut vrefint).await);
 
     loop {
-        let v = adc.read(&mut pin).await;
-        info!("--> {} - {} mV", v, convert_to_millivolts(v));
+        let v = adc.read(&mut pin).await.unwrap();
+        info!("--> {} - {} mV", v, adc.sample_to_mv(v, vdda_mv));
         Timer::after(Duration::from_millis(100)).await;
     }
 }
@@ -0,0 +1,45 @@
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::{Adc, SampleTime};
+use embassy_stm32::peripherals::ADC;
+use embassy_stm32::time::Hertz;
+use embassy_stm32::{adc, bind_interrupts, Config};
+use embassy_time::{Delay, Duration, Instant, Timer};
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    ADC1_COMP => adc::InterruptHandler<ADC>;
+});
+
+/// Number of bursts timed together, to average out the coarse tick of the time driver.
+const BURSTS: u64 = 16;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let mut config = Config::default();
+    config.rcc.sys_ck = Some(Hertz(48_000_000));
+    let p = embassy_stm32::init(config);
+    info!("Hello World!");
+
+    let mut adc = Adc::builder(p.ADC, Irqs)
+        .sample_time(SampleTime::Cycles1_5)
+        .build(&mut Delay);
+    let mut pin = p.PA1;
+    let mut samples = [0u16; 256];
+
+    loop {
+        let start = Instant::now();
+        for _ in 0..BURSTS {
+            unwrap!(adc.capture_burst(&mut pin, &mut samples).await);
+        }
+        let elapsed = start.elapsed().as_micros();
+
+        let sps = BURSTS * samples.len() as u64 * 1_000_000 / elapsed;
+        info!("{} samples/s, first sample of the last burst: {}", sps, samples[0]);
+        Timer::after(Duration::from_secs(1)).await;
+    }
+}
@@ -0,0 +1,60 @@
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use cortex_m::peripheral::syst::SystClkSource;
+use cortex_m::peripheral::SYST;
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::{Adc, Resolution};
+use embassy_stm32::peripherals::ADC;
+use embassy_stm32::time::Hertz;
+use embassy_stm32::{adc, bind_interrupts, Config};
+use embassy_time::{Delay, Duration, Timer};
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    ADC1_COMP => adc::InterruptHandler<ADC>;
+});
+
+const READS: u32 = 10_000;
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let mut config = Config::default();
+    config.rcc.sys_ck = Some(Hertz(48_000_000));
+    let p = embassy_stm32::init(config);
+    info!("Hello World!");
+
+    // The Cortex-M0 has no cycle counter, so time the reads with SysTick, which the time driver
+    // doesn't use.
+    let mut cp = unwrap!(cortex_m::Peripherals::take());
+    cp.SYST.set_clock_source(SystClkSource::Core);
+    cp.SYST.set_reload(0x00FF_FFFF);
+    cp.SYST.clear_current();
+    cp.SYST.enable_counter();
+
+    let mut adc = Adc::new(p.ADC, Irqs, &mut Delay);
+    unwrap!(adc.set_resolution(Resolution::SixBit));
+    let mut pin = p.PA1;
+
+    loop {
+        let mut worst = 0;
+        let mut total = 0;
+        for _ in 0..READS {
+            let start = SYST::get_current();
+            unwrap!(adc.read_fast(&mut pin));
+            // SysTick counts down and wraps at 24 bits.
+            let cycles = start.wrapping_sub(SYST::get_current()) & 0x00FF_FFFF;
+            worst = worst.max(cycles);
+            total += cycles;
+        }
+
+        info!(
+            "read_fast: {} cycles on average, {} worst case at 48 MHz",
+            total / READS,
+            worst
+        );
+        Timer::after(Duration::from_secs(1)).await;
+    }
+}
@@ -0,0 +1,37 @@
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::Adc;
+use embassy_stm32::gpio::{Flex, Pull};
+use embassy_stm32::peripherals::ADC;
+use embassy_stm32::{adc, bind_interrupts};
+use embassy_time::{Delay, Duration, Timer};
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    ADC1_COMP => adc::InterruptHandler<ADC>;
+});
+
+/// Uses PA1 both as a digital input and as an analog input, switching between the two.
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    let mut adc = Adc::new(p.ADC, Irqs, &mut Delay);
+    let mut pin = Flex::new(p.PA1);
+
+    loop {
+        pin.set_as_input(Pull::None);
+        info!("digital: {}", pin.is_high());
+
+        // Reading puts the pin into analog mode.
+        let v = unwrap!(adc.read(&mut pin).await);
+        info!("analog: {}", v);
+
+        Timer::after(Duration::from_millis(100)).await;
+    }
+}
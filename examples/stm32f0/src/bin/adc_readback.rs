@@ -0,0 +1,58 @@
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::{Adc, Resolution, SampleTime};
+use embassy_stm32::peripherals::ADC;
+use embassy_stm32::{adc, bind_interrupts};
+use embassy_time::Delay;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    ADC1_COMP => adc::InterruptHandler<ADC>;
+});
+
+// Checks that the sample time and resolution read back from SMPR and CFGR1 are the ones set,
+// including after a conversion.
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    let mut adc = Adc::new(p.ADC, Irqs, &mut Delay);
+    let mut pin = p.PA1;
+
+    for sample_time in [
+        SampleTime::Cycles1_5,
+        SampleTime::Cycles7_5,
+        SampleTime::Cycles13_5,
+        SampleTime::Cycles28_5,
+        SampleTime::Cycles41_5,
+        SampleTime::Cycles55_5,
+        SampleTime::Cycles71_5,
+        SampleTime::Cycles239_5,
+    ] {
+        adc.set_sample_time(sample_time);
+        assert_eq!(adc.current_sample_time(), sample_time);
+
+        unwrap!(adc.read(&mut pin).await);
+        assert_eq!(adc.current_sample_time(), sample_time);
+    }
+
+    for resolution in [
+        Resolution::TwelveBit,
+        Resolution::TenBit,
+        Resolution::EightBit,
+        Resolution::SixBit,
+    ] {
+        unwrap!(adc.set_resolution(resolution));
+        assert_eq!(adc.current_resolution(), resolution);
+
+        unwrap!(adc.read(&mut pin).await);
+        assert_eq!(adc.current_resolution(), resolution);
+    }
+
+    info!("Test OK");
+}
@@ -0,0 +1,52 @@
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::{Adc, AdcShared, SampleTime};
+use embassy_stm32::peripherals::{ADC, PA1, PA4};
+use embassy_stm32::{adc, bind_interrupts};
+use embassy_sync::blocking_mutex::raw::ThreadModeRawMutex;
+use embassy_time::{Delay, Duration, Timer};
+use static_cell::StaticCell;
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    ADC1_COMP => adc::InterruptHandler<ADC>;
+});
+
+type SharedAdc = AdcShared<'static, ThreadModeRawMutex, ADC>;
+
+static ADC_SHARED: StaticCell<SharedAdc> = StaticCell::new();
+
+#[embassy_executor::task]
+async fn read_pa1(adc: &'static SharedAdc, mut pin: PA1) {
+    loop {
+        let v = unwrap!(adc.read(&mut pin).await);
+        info!("PA1: {}", v);
+        Timer::after(Duration::from_millis(100)).await;
+    }
+}
+
+#[embassy_executor::task]
+async fn read_pa4(adc: &'static SharedAdc, mut pin: PA4) {
+    loop {
+        let v = unwrap!(adc.read(&mut pin).await);
+        info!("PA4: {}", v);
+        Timer::after(Duration::from_millis(250)).await;
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    let mut adc = Adc::new(p.ADC, Irqs, &mut Delay);
+    adc.set_sample_time(SampleTime::Cycles71_5);
+    let adc = ADC_SHARED.init(AdcShared::new(adc));
+
+    unwrap!(spawner.spawn(read_pa1(adc, p.PA1)));
+    unwrap!(spawner.spawn(read_pa4(adc, p.PA4)));
+}
@@ -0,0 +1,36 @@
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use defmt::*;
+use embassy_executor::Spawner;
+use embassy_stm32::adc::{Adc, SampleTime};
+use embassy_stm32::peripherals::ADC;
+use embassy_stm32::{adc, bind_interrupts};
+use embassy_time::{Delay, Duration, Timer};
+use {defmt_rtt as _, panic_probe as _};
+
+bind_interrupts!(struct Irqs {
+    ADC1_COMP => adc::InterruptHandler<ADC>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_stm32::init(Default::default());
+    info!("Hello World!");
+
+    let mut adc = Adc::new(p.ADC, Irqs, &mut Delay);
+    // The temperature sensor needs a sample time of at least 4 µs.
+    adc.set_sample_time(SampleTime::Cycles239_5);
+
+    let mut vrefint = adc.enable_vref(&mut Delay);
+    let mut temperature = adc.enable_temperature(&mut Delay);
+
+    loop {
+        let vrefint_sample = adc.read_internal(&mut vrefint).await.unwrap();
+        let temperature_sample = adc.read_internal(&mut temperature).await.unwrap();
+        let celsius = adc.temperature_to_celsius(temperature_sample, vrefint_sample);
+        info!("--> {} - {} °C", temperature_sample, celsius);
+        Timer::after(Duration::from_millis(1000)).await;
+    }
+}